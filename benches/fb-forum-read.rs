@@ -11,13 +11,23 @@ use rusty_nets::network::reader::*;
 fn bench_fb_forum_read(c: &mut Criterion) {
     c.bench_function("fb-forum read", move |b| {
         b.iter(|| {
-            let reader = EdgeListReader::new(',');
+            let reader = EdgeListReader::new(',', false);
             let path: PathBuf = "resources/nets/fb-forum.txt".parse().unwrap();
             reader.read(path).unwrap()
         })
     });
 }
 
+fn bench_fb_forum_parallel_read(c: &mut Criterion) {
+    c.bench_function("fb-forum parallel read", move |b| {
+        b.iter(|| {
+            let reader = EdgeListReader::new(',', false);
+            let path: PathBuf = "resources/nets/fb-forum.txt".parse().unwrap();
+            reader.read_parallel(path).unwrap()
+        })
+    });
+}
+
 fn bench_fb_forum_embedding_read(c: &mut Criterion) {
     c.bench_function("fb-forum embedding read", move |b| {
         b.iter(|| {
@@ -29,5 +39,10 @@ fn bench_fb_forum_embedding_read(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_fb_forum_read, bench_fb_forum_embedding_read);
+criterion_group!(
+    benches,
+    bench_fb_forum_read,
+    bench_fb_forum_parallel_read,
+    bench_fb_forum_embedding_read
+);
 criterion_main!(benches);
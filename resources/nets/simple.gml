@@ -0,0 +1,15 @@
+# Exported by another tool
+Creator "someone"
+graph [
+  directed 1
+  node [ id 0 label "alice" ]
+  node [ id 1 label "bob" ]
+  node [
+    id 2
+    label "carol"
+  ]
+  node [ id 3 ]
+  edge [ source 0 target 1 weight 16.0 value 3 ]
+  edge [ source 1 target 2 weight 18 ]
+  edge [ source 2 target 0 ]
+]
@@ -3,13 +3,14 @@ use rand::prelude::*;
 
 use crate::network::model::{Network, NetworkNode};
 
-trait RandomWalker {
+pub trait RandomWalker {
     fn walk<N: NetworkNode, E: Copy>(&mut self, net: &Network<N, E>, start_node: N) -> Vec<N>;
 }
 
 pub struct NonBiasedRandomWalker {
     walks_per_node: usize,
     walk_length: usize,
+    seed: Option<u64>,
     rng: StdRng,
 }
 
@@ -18,11 +19,26 @@ impl NonBiasedRandomWalker {
         NonBiasedRandomWalker {
             walks_per_node,
             walk_length,
+            seed: None,
             rng: StdRng::from_entropy(),
         }
     }
 
+    pub fn walks_per_node(&self) -> usize {
+        self.walks_per_node
+    }
+
+    pub fn walk_length(&self) -> usize {
+        self.walk_length
+    }
+
+    /// Seed of the last `set_seed` call, `None` if seeded from entropy
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
     pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
         self.rng = StdRng::seed_from_u64(seed);
     }
 }
@@ -0,0 +1,100 @@
+/// Transparent decompression of input files
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::path::Path;
+
+use bzip2::bufread::MultiBzDecoder;
+use flate2::bufread::MultiGzDecoder;
+
+use crate::error::ReaderError;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+/// Magic of the first block, or of the end of stream for empty input
+const BZIP2_BLOCK_MAGIC: &[u8] = &[0x31, 0x41, 0x59, 0x26, 0x53, 0x59];
+const BZIP2_EOS_MAGIC: &[u8] = &[0x17, 0x72, 0x45, 0x38, 0x50, 0x90];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression of `file` by its magic bytes, falling back to
+    /// its extension (`.gz`, `.bz2`, `.zst`)
+    pub fn detect(file: &Path) -> Result<Compression, io::Error> {
+        Compression::detect_in(&mut io::BufReader::new(fs::File::open(file)?), file)
+    }
+
+    /// Same as `detect`, peeking at the buffer of an already opened `reader`
+    /// without consuming it
+    fn detect_in<R: BufRead>(reader: &mut R, file: &Path) -> Result<Compression, io::Error> {
+        let magic = reader.fill_buf()?;
+
+        Ok(Compression::from_magic(magic).unwrap_or_else(|| Compression::from_extension(file)))
+    }
+
+    fn from_magic(magic: &[u8]) -> Option<Compression> {
+        if magic.starts_with(GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if is_bzip2(magic) {
+            Some(Compression::Bzip2)
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    fn from_extension(file: &Path) -> Compression {
+        match file.extension().and_then(|e| e.to_str()) {
+            Some("gz") | Some("gzip") => Compression::Gzip,
+            Some("bz2") => Compression::Bzip2,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// `BZh`, the block size digit and the magic of the first block, as a bare
+/// `BZh` prefix is common in plain text
+fn is_bzip2(magic: &[u8]) -> bool {
+    magic.len() >= 10
+        && magic.starts_with(BZIP2_MAGIC)
+        && (b'1'..=b'9').contains(&magic[3])
+        && (magic[4..10] == *BZIP2_BLOCK_MAGIC || magic[4..10] == *BZIP2_EOS_MAGIC)
+}
+
+/// Opens `file` for buffered reading, decompressing it on the fly
+pub(crate) fn open(file: &Path) -> Result<Box<dyn BufRead>, io::Error> {
+    let mut f = io::BufReader::new(fs::File::open(file)?);
+    let compression = Compression::detect_in(&mut f, file)?;
+
+    Ok(match compression {
+        Compression::None => Box::new(f),
+        Compression::Gzip => Box::new(io::BufReader::new(MultiGzDecoder::new(f))),
+        Compression::Bzip2 => Box::new(io::BufReader::new(MultiBzDecoder::new(f))),
+        Compression::Zstd => Box::new(io::BufReader::new(zstd::Decoder::with_buffer(f)?)),
+    })
+}
+
+/// Opens `file` and passes the decompressed stream to `read`, tagging any
+/// error with the file path
+pub(crate) fn read_file<T, F>(file: &Path, read: F) -> Result<T, ReaderError>
+where
+    F: FnOnce(Box<dyn BufRead>) -> Result<T, ReaderError>,
+{
+    open(file)
+        .map_err(ReaderError::from)
+        .and_then(read)
+        .map_err(|e| e.with_path(file))
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/compression_tests.rs"]
+mod compression_tests;
@@ -0,0 +1,478 @@
+/// Approximate nearest neighbour index over embedding vectors
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io;
+use std::io::Read;
+use std::path::PathBuf;
+
+use rand::prelude::*;
+
+use crate::compression;
+use crate::embedding::model::*;
+use crate::embedding::similarity::Metric;
+use crate::error::ReaderError;
+use crate::snapshot::{self, Content, SnapshotValue};
+
+const DEFAULT_M: usize = 16;
+const DEFAULT_EF_CONSTRUCTION: usize = 200;
+const DEFAULT_EF_SEARCH: usize = 50;
+
+/// Node reached during a search, ordered by its distance to the query
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f64,
+    node: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Candidate) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Candidate) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Candidate) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+/// Hierarchical navigable small world graph (Malkov & Yashunin, 2016).
+/// Every vector is linked to its approximate nearest neighbours on layer 0
+/// and on a random number of sparser layers above it; searches descend
+/// greedily from the top layer.
+///
+/// `m` is the number of links per node and layer (twice as many on layer
+/// 0), `ef_construction` and `ef_search` the number of candidates kept while
+/// inserting and searching. Larger values give a better recall at the cost
+/// of speed.
+#[derive(Debug)]
+pub struct HnswIndex<K: EmbeddingKey> {
+    dim: usize,
+    metric: Metric,
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    keys: Vec<K>,
+    rows: HashMap<K, usize>,
+    vectors: Vec<f64>,
+    /// Neighbours of every node, one list per layer the node is on
+    links: Vec<Vec<Vec<usize>>>,
+    entry_point: Option<usize>,
+    rng: StdRng,
+}
+
+impl<K: EmbeddingKey> HnswIndex<K> {
+    pub fn new(dim: usize, metric: Metric) -> HnswIndex<K> {
+        HnswIndex {
+            dim,
+            metric,
+            m: DEFAULT_M,
+            ef_construction: DEFAULT_EF_CONSTRUCTION,
+            ef_search: DEFAULT_EF_SEARCH,
+            keys: Vec::new(),
+            rows: HashMap::new(),
+            vectors: Vec::new(),
+            links: Vec::new(),
+            entry_point: None,
+            rng: StdRng::from_entropy(),
+        }
+    }
+
+    /// Number of neighbours an inserted vector is linked to on each layer,
+    /// values below 2 are raised to 2. Layer 0 keeps up to `2 * m` links per
+    /// node, the upper layers up to `m`. Existing links are kept, but lists
+    /// that grow past the new limit are pruned to it.
+    pub fn set_m(&mut self, m: usize) {
+        self.m = m.max(2);
+    }
+
+    pub fn set_ef_construction(&mut self, ef_construction: usize) {
+        self.ef_construction = ef_construction.max(1);
+    }
+
+    pub fn set_ef_search(&mut self, ef_search: usize) {
+        self.ef_search = ef_search.max(1);
+    }
+
+    /// Seeds the choice of layers for inserted vectors
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    pub fn m(&self) -> usize {
+        self.m
+    }
+
+    pub fn ef_construction(&self) -> usize {
+        self.ef_construction
+    }
+
+    pub fn ef_search(&self) -> usize {
+        self.ef_search
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.rows.contains_key(key)
+    }
+
+    pub fn insert_embedding(&mut self, emb: &Embedding<K>) -> Result<(), EmbeddingError> {
+        for (key, vector) in emb.vectors() {
+            self.insert(key.clone(), vector)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds a vector to the index. Keys can't be indexed twice.
+    pub fn insert(&mut self, key: K, vector: &[f64]) -> Result<(), EmbeddingError> {
+        self.check_dim(vector)?;
+        if self.rows.contains_key(&key) {
+            return Err(EmbeddingError::DuplicateKey(format!(
+                "Key {:?} is already indexed",
+                key
+            )));
+        }
+
+        let node = self.keys.len();
+        let level = self.random_level();
+
+        self.rows.insert(key.clone(), node);
+        self.keys.push(key);
+        self.vectors.extend_from_slice(vector);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => {
+                self.entry_point = Some(node);
+                return Ok(());
+            }
+        };
+        let top = self.links[entry].len() - 1;
+
+        let mut nearest = vec![self.candidate(vector, entry)];
+        for layer in (level + 1..=top).rev() {
+            nearest = self.search_layer(vector, nearest, 1, layer);
+        }
+
+        for layer in (0..=level.min(top)).rev() {
+            nearest = self.search_layer(vector, nearest, self.ef_construction, layer);
+
+            let neighbours = self.select_neighbours(&nearest, self.m);
+            for &neighbour in neighbours.iter() {
+                self.links[neighbour][layer].push(node);
+                if self.links[neighbour][layer].len() > self.max_links(layer) {
+                    self.shrink_links(neighbour, layer);
+                }
+            }
+            self.links[node][layer] = neighbours;
+        }
+
+        if level > top {
+            self.entry_point = Some(node);
+        }
+
+        Ok(())
+    }
+
+    /// The approximately `k` most similar keys to `vector`, most similar
+    /// first, with their similarity as in `Embedding::most_similar`
+    pub fn search(&self, vector: &[f64], k: usize) -> Result<Vec<(&K, f64)>, EmbeddingError> {
+        self.check_dim(vector)?;
+
+        let entry = match self.entry_point {
+            Some(entry) => entry,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut nearest = vec![self.candidate(vector, entry)];
+        for layer in (1..self.links[entry].len()).rev() {
+            nearest = self.search_layer(vector, nearest, 1, layer);
+        }
+        nearest = self.search_layer(vector, nearest, self.ef_search.max(k), 0);
+
+        Ok(nearest
+            .into_iter()
+            .take(k)
+            .map(|c| {
+                let score = self.metric.between(vector, self.vector(c.node));
+                (&self.keys[c.node], score)
+            })
+            .collect())
+    }
+
+    fn check_dim(&self, vector: &[f64]) -> Result<(), EmbeddingError> {
+        if vector.len() != self.dim {
+            return Err(EmbeddingError::WrongEmbeddingDimension(format!(
+                "Expected: {} Got: {}",
+                self.dim,
+                vector.len()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn vector(&self, node: usize) -> &[f64] {
+        &self.vectors[node * self.dim..(node + 1) * self.dim]
+    }
+
+    fn candidate(&self, query: &[f64], node: usize) -> Candidate {
+        Candidate {
+            distance: self.metric.distance(query, self.vector(node)),
+            node,
+        }
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            2 * self.m
+        } else {
+            self.m
+        }
+    }
+
+    /// Exponentially distributed layer, each layer holding about `1 / m` of
+    /// the nodes of the one below
+    fn random_level(&mut self) -> usize {
+        let uniform: f64 = self.rng.gen();
+        (-(1.0 - uniform).ln() / (self.m as f64).ln()).floor() as usize
+    }
+
+    /// The `ef` nodes closest to `query` on `layer` found from `entry_points`,
+    /// closest first
+    fn search_layer(
+        &self,
+        query: &[f64],
+        entry_points: Vec<Candidate>,
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().map(|c| c.node).collect();
+        let mut candidates: BinaryHeap<Reverse<Candidate>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        let mut nearest: BinaryHeap<Candidate> = entry_points.into_iter().collect();
+        while nearest.len() > ef {
+            nearest.pop();
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            if current.distance > nearest.peek().unwrap().distance {
+                break;
+            }
+
+            for &neighbour in self.links[current.node][layer].iter() {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+
+                let candidate = self.candidate(query, neighbour);
+                if nearest.len() < ef || candidate < *nearest.peek().unwrap() {
+                    candidates.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+
+        nearest.into_sorted_vec()
+    }
+
+    /// Picks up to `m` of the `candidates` (closest first), preferring
+    /// candidates closer to the query than to the ones already picked so
+    /// links spread in all directions
+    fn select_neighbours(&self, candidates: &[Candidate], m: usize) -> Vec<usize> {
+        let mut selected: Vec<Candidate> = Vec::with_capacity(m);
+        let mut pruned = Vec::new();
+
+        for candidate in candidates.iter() {
+            if selected.len() == m {
+                break;
+            }
+
+            let vector = self.vector(candidate.node);
+            let diverse = selected
+                .iter()
+                .all(|s| self.metric.distance(vector, self.vector(s.node)) > candidate.distance);
+
+            if diverse {
+                selected.push(*candidate);
+            } else {
+                pruned.push(*candidate);
+            }
+        }
+
+        // Fill up with the closest pruned candidates to keep the graph dense
+        let missing = m - selected.len();
+        selected.extend(pruned.into_iter().take(missing));
+
+        selected.into_iter().map(|c| c.node).collect()
+    }
+
+    fn shrink_links(&mut self, node: usize, layer: usize) {
+        let vector = self.vector(node);
+        let mut candidates: Vec<Candidate> = self.links[node][layer]
+            .iter()
+            .map(|&n| self.candidate(vector, n))
+            .collect();
+        candidates.sort();
+
+        self.links[node][layer] = self.select_neighbours(&candidates, self.max_links(layer));
+    }
+}
+
+impl<K: EmbeddingKey + SnapshotValue> HnswIndex<K> {
+    /// Writes the index as a snapshot (see `crate::snapshot`), so it can be
+    /// loaded without being rebuilt
+    pub fn save(&self, file: PathBuf) -> Result<(), io::Error> {
+        let mut payload = Vec::new();
+
+        (self.metric as u8).encode(&mut payload);
+        self.dim.encode(&mut payload);
+        self.m.encode(&mut payload);
+        self.ef_construction.encode(&mut payload);
+        self.ef_search.encode(&mut payload);
+        self.entry_point.unwrap_or(0).encode(&mut payload);
+
+        self.keys.len().encode(&mut payload);
+        for (node, key) in self.keys.iter().enumerate() {
+            key.encode(&mut payload);
+            for v in self.vector(node) {
+                v.encode(&mut payload);
+            }
+
+            self.links[node].len().encode(&mut payload);
+            for neighbours in self.links[node].iter() {
+                neighbours.len().encode(&mut payload);
+                for neighbour in neighbours.iter() {
+                    neighbour.encode(&mut payload);
+                }
+            }
+        }
+
+        snapshot::write_snapshot(
+            file,
+            Content::HnswIndex,
+            [K::TYPE_NAME, f64::TYPE_NAME],
+            &payload,
+        )
+    }
+
+    /// Reads an index written by `save`. Vectors inserted afterwards get
+    /// their layers from a fresh random generator unless `set_seed` is used.
+    pub fn load(file: PathBuf) -> Result<HnswIndex<K>, ReaderError> {
+        compression::read_file(&file, |mut reader| {
+            let mut data = Vec::new();
+            reader.read_to_end(&mut data)?;
+
+            decode_index(&data)
+        })
+    }
+}
+
+fn decode_index<K: EmbeddingKey + SnapshotValue>(data: &[u8]) -> Result<HnswIndex<K>, ReaderError> {
+    let mut input =
+        snapshot::read_payload(data, Content::HnswIndex, [K::TYPE_NAME, f64::TYPE_NAME])?;
+    let input = &mut input;
+
+    let metric = match u8::decode(input)? {
+        m if m == Metric::Cosine as u8 => Metric::Cosine,
+        m if m == Metric::Dot as u8 => Metric::Dot,
+        m if m == Metric::Euclidean as u8 => Metric::Euclidean,
+        _ => return Err(ReaderError::malformed("Unknown similarity metric")),
+    };
+
+    let mut index = HnswIndex::new(usize::decode(input)?, metric);
+    index.set_m(usize::decode(input)?);
+    index.set_ef_construction(usize::decode(input)?);
+    index.set_ef_search(usize::decode(input)?);
+    let entry_point = usize::decode(input)?;
+
+    let num_nodes = usize::decode(input)?;
+    for node in 0..num_nodes {
+        let key = K::decode(input)?;
+        if index.rows.insert(key.clone(), node).is_some() {
+            return Err(ReaderError::malformed("Duplicate key in snapshot"));
+        }
+        index.keys.push(key);
+
+        for _ in 0..index.dim {
+            index.vectors.push(f64::decode(input)?);
+        }
+
+        let num_layers = usize::decode(input)?;
+        let mut layers = Vec::with_capacity(snapshot::capacity(num_layers, input));
+        for _ in 0..num_layers {
+            let len = usize::decode(input)?;
+            let mut neighbours = Vec::with_capacity(snapshot::capacity(len, input));
+            for _ in 0..len {
+                neighbours.push(usize::decode(input)?);
+            }
+            layers.push(neighbours);
+        }
+        index.links.push(layers);
+    }
+
+    if !input.is_empty() {
+        return Err(ReaderError::malformed("Trailing data in payload"));
+    }
+
+    // Searches follow the links blindly, every linked node has to exist on
+    // the layer of the link
+    let links = &index.links;
+    let valid = links.iter().all(|layers| {
+        !layers.is_empty()
+            && layers.iter().enumerate().all(|(layer, neighbours)| {
+                neighbours
+                    .iter()
+                    .all(|&n| n < num_nodes && links[n].len() > layer)
+            })
+    });
+    if !valid || (num_nodes > 0 && entry_point >= num_nodes) {
+        return Err(ReaderError::malformed("Invalid index links"));
+    }
+    // Searches only descend from the entry point, nodes above it are lost
+    let top = links.iter().map(|layers| layers.len()).max();
+    if num_nodes > 0 && Some(links[entry_point].len()) != top {
+        return Err(ReaderError::malformed(
+            "Entry point is not on the top layer",
+        ));
+    }
+    if num_nodes > 0 {
+        index.entry_point = Some(entry_point);
+    }
+
+    Ok(index)
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/embedding/hnsw_tests.rs"]
+mod hnsw_tests;
@@ -1,3 +1,7 @@
 /// Embedding related code
+pub mod hnsw;
 pub mod model;
+mod npy;
 pub mod reader;
+pub mod similarity;
+pub mod writer;
@@ -2,54 +2,93 @@
 use std::cmp::Eq;
 use std::collections::HashMap;
 use std::default::Default;
+use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
 
 use ndarray::prelude::*;
 
+use crate::embedding::similarity::{self, Metric};
+
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EmbeddingError {
     WrongEmbeddingDimension(String),
     VectorNotFound(String),
+    DuplicateKey(String),
+}
+
+impl fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EmbeddingError::WrongEmbeddingDimension(msg) => {
+                write!(f, "Wrong embedding dimension ({})", msg)
+            }
+            EmbeddingError::VectorNotFound(msg) | EmbeddingError::DuplicateKey(msg) => {
+                write!(f, "{}", msg)
+            }
+        }
+    }
 }
 
-pub trait EmbeddingKey: Eq + Hash + Copy + Default + Debug {}
+pub trait EmbeddingKey: Eq + Hash + Clone + Default + Debug {}
 
-impl<T> EmbeddingKey for T where T: Eq + Hash + Copy + Default + Debug {}
+impl<T> EmbeddingKey for T where T: Eq + Hash + Clone + Default + Debug {}
 
+/// Vectors are stored as the rows of a single matrix, in insertion order,
+/// with an index from keys to rows
 #[derive(Debug, Default)]
 pub struct Embedding<K: EmbeddingKey> {
     emb_dim: usize,
-    emb_vectors: HashMap<K, Array1<f64>>,
+    emb_keys: Vec<K>,
+    emb_rows: HashMap<K, usize>,
+    emb_vectors: Array2<f64>,
 }
 
 impl<K: EmbeddingKey> Embedding<K> {
     pub fn new(dim: usize) -> Embedding<K> {
         Embedding {
             emb_dim: dim,
-            emb_vectors: HashMap::new(),
+            emb_keys: Vec::new(),
+            emb_rows: HashMap::new(),
+            emb_vectors: Array2::zeros((0, dim)),
         }
     }
 
+    /// Adds a vector, replacing the one of `key` if there was one
     pub fn add_vector(&mut self, key: K, vector: &[f64]) -> Result<(), EmbeddingError> {
-        let vector: Array1<f64> = Array1::from_vec(Vec::from(vector));
-
-        if vector.dim() != self.emb_dim {
+        if vector.len() != self.emb_dim {
             return Err(EmbeddingError::WrongEmbeddingDimension(format!(
                 "Expected: {} Got: {}",
                 self.emb_dim,
-                vector.dim()
+                vector.len()
             )));
         }
 
-        self.emb_vectors.insert(key, vector);
+        if let Some(&row) = self.emb_rows.get(&key) {
+            self.emb_vectors
+                .row_mut(row)
+                .assign(&ArrayView1::from(vector));
+            return Ok(());
+        }
+
+        // The raw vector grows with amortized doubling, so appending a row
+        // doesn't copy the whole matrix every time
+        let num_rows = self.emb_keys.len() + 1;
+        let mut values =
+            std::mem::replace(&mut self.emb_vectors, Array2::zeros((0, 0))).into_raw_vec();
+        values.extend_from_slice(vector);
+        self.emb_vectors = Array2::from_shape_vec((num_rows, self.emb_dim), values).unwrap();
+
+        self.emb_rows.insert(key.clone(), self.emb_keys.len());
+        self.emb_keys.push(key);
 
         Ok(())
     }
 
     pub fn get_vector(&self, key: K) -> Result<&[f64], EmbeddingError> {
-        match self.emb_vectors.get(&key) {
-            Some(v) => Ok(v.as_slice().unwrap()),
+        match self.emb_rows.get(&key) {
+            Some(&row) => Ok(self.row_vector(row)),
             None => Err(EmbeddingError::VectorNotFound(format!(
                 "No vector found for {:?}",
                 key
@@ -61,8 +100,85 @@ impl<K: EmbeddingKey> Embedding<K> {
         self.emb_dim
     }
 
+    /// Keys in row order of `as_matrix`
     pub fn keys(&self) -> Vec<&K> {
-        self.emb_vectors.keys().collect()
+        self.emb_keys.iter().collect()
+    }
+
+    /// Every key with its vector, in row order of `as_matrix`
+    pub fn vectors(&self) -> Vec<(&K, &[f64])> {
+        self.emb_keys
+            .iter()
+            .enumerate()
+            .map(|(row, key)| (key, self.row_vector(row)))
+            .collect()
+    }
+
+    /// Row of the vector of `key` in `as_matrix`
+    pub fn row(&self, key: K) -> Option<usize> {
+        self.emb_rows.get(&key).copied()
+    }
+
+    /// All vectors as the rows of one `keys().len() x dim()` matrix, for
+    /// batch operations
+    pub fn as_matrix(&self) -> ArrayView2<'_, f64> {
+        self.emb_vectors.view()
+    }
+
+    /// Similarity of the vectors of `a` and `b`
+    pub fn similarity(&self, a: K, b: K, metric: Metric) -> Result<f64, EmbeddingError> {
+        Ok(metric.between(self.get_vector(a)?, self.get_vector(b)?))
+    }
+
+    /// The `k` keys most similar to `key`, most similar first, excluding
+    /// `key` itself
+    pub fn most_similar(
+        &self,
+        key: K,
+        k: usize,
+        metric: Metric,
+    ) -> Result<Vec<(&K, f64)>, EmbeddingError> {
+        let vector = self.get_vector(key.clone())?;
+        let mut similar = self.most_similar_to_vector(vector, k.saturating_add(1), metric)?;
+
+        match similar.iter().position(|(other, _)| **other == key) {
+            Some(pos) => {
+                similar.remove(pos);
+            }
+            None => similar.truncate(k),
+        }
+
+        Ok(similar)
+    }
+
+    /// The `k` keys whose vectors are most similar to `vector`, most similar
+    /// first
+    pub fn most_similar_to_vector(
+        &self,
+        vector: &[f64],
+        k: usize,
+        metric: Metric,
+    ) -> Result<Vec<(&K, f64)>, EmbeddingError> {
+        if vector.len() != self.emb_dim {
+            return Err(EmbeddingError::WrongEmbeddingDimension(format!(
+                "Expected: {} Got: {}",
+                self.emb_dim,
+                vector.len()
+            )));
+        }
+
+        let scores = similarity::scores(self.as_matrix(), ArrayView1::from(vector), metric);
+        let scores = scores.as_slice().unwrap();
+
+        Ok(similarity::top_k(scores, k, metric)
+            .into_iter()
+            .map(|row| (&self.emb_keys[row], scores[row]))
+            .collect())
+    }
+
+    fn row_vector(&self, row: usize) -> &[f64] {
+        let values = self.emb_vectors.as_slice().unwrap();
+        &values[row * self.emb_dim..(row + 1) * self.emb_dim]
     }
 }
 
@@ -0,0 +1,253 @@
+/// NumPy `.npy` array format
+use std::io;
+use std::io::{Read, Write};
+
+use crate::error::{parse_token, ReaderError};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const HEADER_ALIGNMENT: usize = 64;
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum NpyData {
+    Float(Vec<f64>),
+    Int(Vec<i64>),
+    Unicode(Vec<String>),
+}
+
+/// Array read from a `.npy` file, always in row-major order
+#[derive(Debug, PartialEq)]
+pub(crate) struct NpyArray {
+    pub shape: Vec<usize>,
+    pub data: NpyData,
+}
+
+fn write_header<W: Write>(writer: &mut W, descr: &str, shape: &[usize]) -> Result<(), io::Error> {
+    let shape: Vec<String> = shape.iter().map(|s| s.to_string()).collect();
+    let shape = match shape.len() {
+        1 => format!("({},)", shape[0]),
+        _ => format!("({})", shape.join(", ")),
+    };
+
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr, shape
+    );
+
+    // Magic, version and header length precede the header, which ends with
+    // a newline and is padded so that the data is aligned
+    let unpadded = MAGIC.len() + 2 + 2 + header.len() + 1;
+    let padding = (HEADER_ALIGNMENT - unpadded % HEADER_ALIGNMENT) % HEADER_ALIGNMENT;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&[1, 0])?;
+    writer.write_all(&(header.len() as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())
+}
+
+/// Writes a `rows x cols` little-endian f64 matrix given in row-major order
+pub(crate) fn write_matrix<W: Write>(
+    writer: &mut W,
+    rows: usize,
+    cols: usize,
+    values: &[f64],
+) -> Result<(), io::Error> {
+    write_header(writer, "<f8", &[rows, cols])?;
+
+    for v in values.iter() {
+        writer.write_all(&v.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Writes a 1-D array of fixed width unicode strings (UTF-32)
+pub(crate) fn write_strings<W: Write>(writer: &mut W, values: &[String]) -> Result<(), io::Error> {
+    let width = values
+        .iter()
+        .map(|v| v.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    write_header(writer, &format!("<U{}", width), &[values.len()])?;
+
+    for v in values.iter() {
+        let mut num_chars = 0;
+        for c in v.chars() {
+            writer.write_all(&(c as u32).to_le_bytes())?;
+            num_chars += 1;
+        }
+        for _ in num_chars..width {
+            writer.write_all(&[0; 4])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Value of `key` in the header dictionary, up to the next top-level comma
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, ReaderError> {
+    let pattern = format!("'{}':", key);
+    let start = header
+        .find(&pattern)
+        .ok_or_else(|| ReaderError::malformed(&format!("Missing '{}' in .npy header", key)))?
+        + pattern.len();
+    let value = header[start..].trim_start();
+
+    let end = if value.starts_with('(') {
+        value.find(')').map(|e| e + 1)
+    } else {
+        value.find([',', '}'])
+    };
+
+    Ok(value[..end.unwrap_or(value.len())].trim())
+}
+
+pub(crate) fn read_array<R: Read>(reader: &mut R) -> Result<NpyArray, ReaderError> {
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic).map_err(eof_error)?;
+
+    if magic[..6] != *MAGIC {
+        return Err(ReaderError::malformed("Not a .npy file"));
+    }
+
+    let header_len = match magic[6] {
+        1 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).map_err(eof_error)?;
+            u16::from_le_bytes(len) as usize
+        }
+        2 | 3 => {
+            let mut len = [0u8; 4];
+            reader.read_exact(&mut len).map_err(eof_error)?;
+            u32::from_le_bytes(len) as usize
+        }
+        v => {
+            return Err(
+                ReaderError::malformed("Unsupported .npy version").with_token(&v.to_string())
+            )
+        }
+    };
+
+    let header = read_bytes(reader, header_len)?;
+    let header = String::from_utf8_lossy(&header);
+
+    let descr = header_value(&header, "descr")?.trim_matches('\'');
+    let fortran_order = header_value(&header, "fortran_order")? == "True";
+
+    let mut shape: Vec<usize> = Vec::new();
+    for dim in header_value(&header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(|d| d.trim())
+        .filter(|d| !d.is_empty())
+    {
+        shape.push(parse_token(dim, "int")?);
+    }
+
+    let len = shape
+        .iter()
+        .try_fold(1usize, |len, dim| len.checked_mul(*dim))
+        .ok_or_else(|| ReaderError::malformed("Array shape is too large"))?;
+
+    let data = match descr {
+        "<f8" => NpyData::Float(read_values(reader, len, 8, |b| {
+            f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        })?),
+        "<f4" => NpyData::Float(read_values(reader, len, 4, |b| {
+            f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64
+        })?),
+        "<i8" => NpyData::Int(read_values(reader, len, 8, |b| {
+            i64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+        })?),
+        _ if descr.starts_with("<U") => {
+            let width: usize = parse_token(&descr[2..], "int")?;
+            let size = match width.checked_mul(4) {
+                Some(size) if size > 0 => size,
+                _ => {
+                    return Err(
+                        ReaderError::malformed("Unsupported .npy data type").with_token(descr)
+                    )
+                }
+            };
+            let values = read_values(reader, len, size, |b| {
+                b.chunks(4)
+                    .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .take_while(|c| *c != 0)
+                    .filter_map(std::char::from_u32)
+                    .collect::<String>()
+            })?;
+            NpyData::Unicode(values)
+        }
+        _ => {
+            return Err(ReaderError::malformed("Unsupported .npy data type").with_token(descr));
+        }
+    };
+
+    let data = if fortran_order && shape.len() == 2 {
+        to_row_major(data, shape[0], shape[1])
+    } else {
+        data
+    };
+
+    Ok(NpyArray { shape, data })
+}
+
+fn read_values<R, T, F>(
+    reader: &mut R,
+    len: usize,
+    size: usize,
+    f: F,
+) -> Result<Vec<T>, ReaderError>
+where
+    R: Read,
+    F: Fn(&[u8]) -> T,
+{
+    let num_bytes = len
+        .checked_mul(size)
+        .ok_or_else(|| ReaderError::malformed("Array shape is too large"))?;
+    let raw = read_bytes(reader, num_bytes)?;
+
+    Ok(raw.chunks(size).map(f).collect())
+}
+
+/// Reads exactly `len` bytes. The buffer grows with the data actually read,
+/// so a corrupted length fails at the end of the input instead of
+/// exhausting memory.
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> Result<Vec<u8>, ReaderError> {
+    let mut raw = Vec::new();
+    reader.take(len as u64).read_to_end(&mut raw)?;
+
+    if raw.len() < len {
+        return Err(ReaderError::malformed("Unexpected end of file"));
+    }
+
+    Ok(raw)
+}
+
+fn eof_error(e: io::Error) -> ReaderError {
+    match e.kind() {
+        io::ErrorKind::UnexpectedEof => ReaderError::malformed("Unexpected end of file"),
+        _ => ReaderError::from(e),
+    }
+}
+
+fn to_row_major(data: NpyData, rows: usize, cols: usize) -> NpyData {
+    fn transpose<T: Clone>(values: Vec<T>, rows: usize, cols: usize) -> Vec<T> {
+        (0..rows * cols)
+            .map(|idx| values[(idx % cols) * rows + idx / cols].clone())
+            .collect()
+    }
+
+    match data {
+        NpyData::Float(v) => NpyData::Float(transpose(v, rows, cols)),
+        NpyData::Int(v) => NpyData::Int(transpose(v, rows, cols)),
+        NpyData::Unicode(v) => NpyData::Unicode(transpose(v, rows, cols)),
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/embedding/npy_tests.rs"]
+mod npy_tests;
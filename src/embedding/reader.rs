@@ -1,93 +1,583 @@
 /// Embedding file reader
 use std::fs;
 use std::io;
-use std::io::BufRead;
-use std::num;
-use std::path::PathBuf;
+use std::io::{BufRead, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::compression;
 use crate::embedding::model::*;
+use crate::embedding::npy::{self, NpyArray, NpyData};
+use crate::error::parse_token;
+pub use crate::error::{ErrorKind, ReadReport, ReaderError};
+use crate::network::vocabulary::Vocabulary;
+
+pub trait EmbeddingReader {
+    type K: EmbeddingKey;
 
-#[derive(Debug, PartialEq)]
-pub enum ReaderError {
-    FileNotFound,
-    MalformedFileFormat(String),
+    /// Reads an embedding from any buffered source, e.g. standard input or an
+    /// in-memory buffer. The input is read as is, without decompression.
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Embedding<Self::K>, ReaderError>;
+
+    /// Reads an embedding from `file`, decompressing it if needed. Errors
+    /// carry the file path.
+    fn read(&self, file: PathBuf) -> Result<Embedding<Self::K>, ReaderError> {
+        compression::read_file(&file, |reader| self.read_from(reader))
+    }
 }
 
-impl From<io::Error> for ReaderError {
-    fn from(e: io::Error) -> Self {
-        match e.kind() {
-            io::ErrorKind::NotFound => ReaderError::FileNotFound,
-            _ => panic!(format!("Unknown error occurred: {:?}", e.kind())),
+/// Turns the textual key of a vector into an embedding key
+pub type KeyParser<K> = Box<dyn Fn(&str) -> Result<K, ReaderError> + Send + Sync>;
+
+fn int_key_parser() -> KeyParser<usize> {
+    Box::new(|key| parse_token(key, "int"))
+}
+
+fn from_str_key_parser<K: FromStr>() -> KeyParser<K> {
+    Box::new(|key| parse_token(key, "key"))
+}
+
+/// Reads the word2vec text format. Keys are unsigned integers by default;
+/// `with_key_type` reads any `FromStr` key and `with_key_parser` takes a
+/// custom parser, e.g. for composite keys.
+pub struct W2VEmbeddingVectorsReader<K = usize> {
+    parse_key: KeyParser<K>,
+    strict: bool,
+}
+
+impl Default for W2VEmbeddingVectorsReader {
+    fn default() -> Self {
+        W2VEmbeddingVectorsReader::new()
+    }
+}
+
+impl W2VEmbeddingVectorsReader {
+    pub fn new() -> W2VEmbeddingVectorsReader {
+        W2VEmbeddingVectorsReader {
+            parse_key: int_key_parser(),
+            strict: false,
         }
     }
 }
 
-impl From<num::ParseIntError> for ReaderError {
-    fn from(_: num::ParseIntError) -> Self {
-        ReaderError::MalformedFileFormat("Couldn't parse int".to_string())
+impl<K: EmbeddingKey> W2VEmbeddingVectorsReader<K> {
+    pub fn with_key_type() -> W2VEmbeddingVectorsReader<K>
+    where
+        K: FromStr,
+    {
+        W2VEmbeddingVectorsReader {
+            parse_key: from_str_key_parser(),
+            strict: false,
+        }
+    }
+
+    pub fn with_key_parser<F>(parse_key: F) -> W2VEmbeddingVectorsReader<K>
+    where
+        F: Fn(&str) -> Result<K, ReaderError> + Send + Sync + 'static,
+    {
+        W2VEmbeddingVectorsReader {
+            parse_key: Box::new(parse_key),
+            strict: false,
+        }
+    }
+
+    /// In strict mode the number of rows must match the header, and
+    /// duplicate keys and NaN or infinite values are rejected. Rows skipped
+    /// by `read_lenient` still count, as the header describes the file.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
     }
 }
 
-impl From<num::ParseFloatError> for ReaderError {
-    fn from(_: num::ParseFloatError) -> Self {
-        ReaderError::MalformedFileFormat("Couldn't parse float".to_string())
+impl<K: EmbeddingKey> EmbeddingReader for W2VEmbeddingVectorsReader<K> {
+    type K = K;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Embedding<K>, ReaderError> {
+        let (emb, _) = self.read_lines(reader, None)?;
+
+        Ok(emb)
     }
 }
 
-pub trait EmbeddingReader {
-    fn read(&self, file: PathBuf) -> Result<Embedding<usize>, ReaderError>;
+impl<K: EmbeddingKey> W2VEmbeddingVectorsReader<K> {
+    /// Reads the embedding skipping up to `max_skipped` malformed rows. A
+    /// malformed header is never skipped.
+    pub fn read_lenient(
+        &self,
+        file: PathBuf,
+        max_skipped: usize,
+    ) -> Result<(Embedding<K>, ReadReport), ReaderError> {
+        let (emb, report) =
+            compression::read_file(&file, |reader| self.read_lines(reader, Some(max_skipped)))?;
+
+        Ok((emb, report.with_path(&file)))
+    }
+
+    fn read_lines<R: BufRead>(
+        &self,
+        reader: R,
+        max_skipped: Option<usize>,
+    ) -> Result<(Embedding<K>, ReadReport), ReaderError> {
+        let mut lines = reader.lines();
+
+        let header_raw: String = match lines.next() {
+            Some(line) => line.map_err(|e| ReaderError::from(e).at_line(1))?,
+            None => return Err(ReaderError::malformed("Missing header").at_line(1)),
+        };
+        let (num_nodes, dim) = parse_header(&header_raw)?;
+
+        let mut emb = Embedding::new(dim);
+        let mut report = ReadReport::new();
+        let mut num_rows = 0;
+
+        for (idx, line) in lines.enumerate() {
+            // The header is line 1
+            let line_no = idx + 2;
+            num_rows += 1;
+
+            let row = line.map_err(ReaderError::from).and_then(|l| {
+                let (node, vector, tokens) = parse_row(&l, dim, &self.parse_key)?;
+                if self.strict {
+                    check_row(&emb, &tokens, &node, &vector)?;
+                }
+
+                Ok((node, vector))
+            });
+
+            match row {
+                Ok((node, vector)) => emb
+                    .add_vector(node, vector.as_slice())
+                    .map_err(|e| ReaderError::from(e).at_line(line_no))?,
+                Err(e) => report.skip(e.at_line(line_no), max_skipped)?,
+            }
+        }
+
+        if self.strict && num_rows != num_nodes {
+            return Err(ReaderError::new(ErrorKind::RowCountMismatch {
+                expected: num_nodes,
+                found: num_rows,
+            }));
+        }
+
+        Ok((emb, report))
+    }
 }
 
-pub struct W2VEmbeddingVectorsReader {}
+/// Strict mode checks of a parsed text row against the rows read before.
+/// `tokens` are the key and value tokens the row was parsed from.
+fn check_row<K: EmbeddingKey>(
+    emb: &Embedding<K>,
+    tokens: &[&str],
+    node: &K,
+    vector: &[f64],
+) -> Result<(), ReaderError> {
+    if emb.get_vector(node.clone()).is_ok() {
+        return Err(ReaderError::new(ErrorKind::DuplicateKey).with_token(tokens[0]));
+    }
 
-impl W2VEmbeddingVectorsReader {
-    pub fn new() -> W2VEmbeddingVectorsReader {
-        W2VEmbeddingVectorsReader {}
+    if let Some(pos) = vector.iter().position(|v| !v.is_finite()) {
+        return Err(ReaderError::new(ErrorKind::NonFiniteValue).with_token(tokens[pos + 1]));
     }
+
+    Ok(())
 }
 
-impl EmbeddingReader for W2VEmbeddingVectorsReader {
-    fn read(&self, file: PathBuf) -> Result<Embedding<usize>, ReaderError> {
-        let f = fs::File::open(file)?;
-        let reader = io::BufReader::new(&f);
+/// Reads the word2vec binary format: a `num_nodes dim` text header followed
+/// by `key<space>` and `dim` little-endian f32 values per vector. A newline
+/// after each vector, as written by the original word2vec tool, is optional.
+pub struct W2VBinaryEmbeddingReader<K = usize> {
+    parse_key: KeyParser<K>,
+}
 
-        let mut lines = reader.lines();
+impl Default for W2VBinaryEmbeddingReader {
+    fn default() -> Self {
+        W2VBinaryEmbeddingReader::new()
+    }
+}
 
-        let header_raw: String = lines.next().unwrap().unwrap();
-        let header: Vec<&str> = header_raw.split(' ').collect();
+impl W2VBinaryEmbeddingReader {
+    pub fn new() -> W2VBinaryEmbeddingReader {
+        W2VBinaryEmbeddingReader {
+            parse_key: int_key_parser(),
+        }
+    }
+
+    /// Reads an embedding with arbitrary textual keys. The keys are interned
+    /// into consecutive integers, the returned vocabulary maps between both.
+    pub fn read_interned(
+        &self,
+        file: PathBuf,
+    ) -> Result<(Embedding<usize>, Vocabulary), ReaderError> {
+        let mut vocab = Vocabulary::new();
+        let emb = compression::read_file(&file, |reader| {
+            read_binary_records(reader, |key| Ok(vocab.intern(key)))
+        })?;
+
+        Ok((emb, vocab))
+    }
+}
 
-        if header.len() != 2 {
-            return Err(ReaderError::MalformedFileFormat(
-                "Header should contain 2 elements: number of nodes and embedding dimension"
-                    .to_string(),
-            ));
+impl<K: EmbeddingKey> W2VBinaryEmbeddingReader<K> {
+    pub fn with_key_type() -> W2VBinaryEmbeddingReader<K>
+    where
+        K: FromStr,
+    {
+        W2VBinaryEmbeddingReader {
+            parse_key: from_str_key_parser(),
         }
+    }
 
-        let dim: usize = header[1].parse()?;
+    pub fn with_key_parser<F>(parse_key: F) -> W2VBinaryEmbeddingReader<K>
+    where
+        F: Fn(&str) -> Result<K, ReaderError> + Send + Sync + 'static,
+    {
+        W2VBinaryEmbeddingReader {
+            parse_key: Box::new(parse_key),
+        }
+    }
+}
 
-        let mut emb = Embedding::new(dim);
+impl<K: EmbeddingKey> EmbeddingReader for W2VBinaryEmbeddingReader<K> {
+    type K = K;
 
-        for line in lines {
-            let emb_map_raw: String = line?;
-            let emb_map: Vec<&str> = emb_map_raw.split(' ').collect();
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Embedding<K>, ReaderError> {
+        read_binary_records(reader, &self.parse_key)
+    }
+}
 
-            if emb_map.len() != (dim + 1) {
-                return Err(ReaderError::MalformedFileFormat(
-                    "Not enough elements".to_string(),
-                ));
-            }
+fn read_binary_records<K, R, F>(
+    mut reader: R,
+    mut parse_key: F,
+) -> Result<Embedding<K>, ReaderError>
+where
+    K: EmbeddingKey,
+    R: BufRead,
+    F: FnMut(&str) -> Result<K, ReaderError>,
+{
+    let mut header_raw = Vec::new();
+    reader
+        .read_until(b'\n', &mut header_raw)
+        .map_err(|e| ReaderError::from(e).at_line(1))?;
+
+    if header_raw.is_empty() {
+        return Err(ReaderError::malformed("Missing header").at_line(1));
+    }
+
+    let header_raw = String::from_utf8_lossy(&header_raw);
+    let (num_nodes, dim) = parse_header(header_raw.trim_end())?;
+
+    let vector_len = dim
+        .checked_mul(4)
+        .ok_or_else(|| ReaderError::malformed("Embedding dimension is too large").at_line(1))?;
+
+    let mut emb = Embedding::new(dim);
+    let mut key_raw = Vec::new();
+    // Filled by reading, so a bogus header dimension can't allocate more
+    // than the input holds
+    let mut vector_raw = Vec::new();
+
+    for _ in 0..num_nodes {
+        key_raw.clear();
+        reader.read_until(b' ', &mut key_raw)?;
 
-            let node: usize = emb_map[0].parse()?;
-            let mut vector: Vec<f64> = Vec::new();
-            for v in emb_map[1..].iter() {
-                vector.push(v.parse()?);
+        if key_raw.pop() != Some(b' ') {
+            return Err(ReaderError::malformed("Unexpected end of file"));
+        }
+
+        let key_start = key_raw.iter().take_while(|b| **b == b'\n').count();
+        let key = std::str::from_utf8(&key_raw[key_start..])
+            .map_err(|_| ReaderError::malformed("Key is not valid UTF-8"))?;
+
+        vector_raw.clear();
+        (&mut reader)
+            .take(vector_len as u64)
+            .read_to_end(&mut vector_raw)?;
+
+        if vector_raw.len() < vector_len {
+            return Err(ReaderError::malformed("Unexpected end of file").with_token(key));
+        }
+
+        let vector: Vec<f64> = vector_raw
+            .chunks(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect();
+
+        emb.add_vector(parse_key(key)?, vector.as_slice())?;
+    }
+
+    Ok(emb)
+}
+
+/// Reads a `num_nodes x dim` `.npy` matrix (f64 or f32) together with a
+/// companion text file holding the key of each row, one per line.
+pub struct NpyEmbeddingReader<K = usize> {
+    keys_file: PathBuf,
+    parse_key: KeyParser<K>,
+}
+
+impl NpyEmbeddingReader {
+    pub fn new(keys_file: PathBuf) -> NpyEmbeddingReader {
+        NpyEmbeddingReader {
+            keys_file,
+            parse_key: int_key_parser(),
+        }
+    }
+
+    /// Reads an embedding with arbitrary textual keys. The keys are interned
+    /// into consecutive integers, the returned vocabulary maps between both.
+    pub fn read_interned(
+        &self,
+        file: PathBuf,
+    ) -> Result<(Embedding<usize>, Vocabulary), ReaderError> {
+        let mut vocab = Vocabulary::new();
+        let emb = compression::read_file(&file, |reader| {
+            self.read_arrays(reader, |key| Ok(vocab.intern(key)))
+        })?;
+
+        Ok((emb, vocab))
+    }
+}
+
+impl<K: EmbeddingKey> NpyEmbeddingReader<K> {
+    pub fn with_key_type(keys_file: PathBuf) -> NpyEmbeddingReader<K>
+    where
+        K: FromStr,
+    {
+        NpyEmbeddingReader {
+            keys_file,
+            parse_key: from_str_key_parser(),
+        }
+    }
+
+    pub fn with_key_parser<F>(keys_file: PathBuf, parse_key: F) -> NpyEmbeddingReader<K>
+    where
+        F: Fn(&str) -> Result<K, ReaderError> + Send + Sync + 'static,
+    {
+        NpyEmbeddingReader {
+            keys_file,
+            parse_key: Box::new(parse_key),
+        }
+    }
+
+    fn read_arrays<R, F>(&self, mut reader: R, parse_key: F) -> Result<Embedding<K>, ReaderError>
+    where
+        R: BufRead,
+        F: FnMut(&str) -> Result<K, ReaderError>,
+    {
+        let mut keys: Vec<String> = Vec::new();
+        let keys_reader = io::BufReader::new(
+            fs::File::open(&self.keys_file)
+                .map_err(|e| ReaderError::from(e).with_path(&self.keys_file))?,
+        );
+        for (idx, line) in keys_reader.lines().enumerate() {
+            keys.push(line.map_err(|e| {
+                ReaderError::from(e)
+                    .at_line(idx + 1)
+                    .with_path(&self.keys_file)
+            })?);
+        }
+
+        let vectors = npy::read_array(&mut reader)?;
+
+        embedding_from_arrays(vectors, keys, parse_key)
+    }
+}
+
+impl<K: EmbeddingKey> EmbeddingReader for NpyEmbeddingReader<K> {
+    type K = K;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Embedding<K>, ReaderError> {
+        self.read_arrays(reader, &self.parse_key)
+    }
+}
+
+/// Reads a `.npz` archive holding a `vectors` matrix and the `keys` of its
+/// rows, as written by `NpzEmbeddingWriter` or `numpy.savez`.
+pub struct NpzEmbeddingReader<K = usize> {
+    parse_key: KeyParser<K>,
+}
+
+impl Default for NpzEmbeddingReader {
+    fn default() -> Self {
+        NpzEmbeddingReader::new()
+    }
+}
+
+impl NpzEmbeddingReader {
+    pub fn new() -> NpzEmbeddingReader {
+        NpzEmbeddingReader {
+            parse_key: int_key_parser(),
+        }
+    }
+
+    /// Reads an embedding with arbitrary textual keys. The keys are interned
+    /// into consecutive integers, the returned vocabulary maps between both.
+    pub fn read_interned(
+        &self,
+        file: PathBuf,
+    ) -> Result<(Embedding<usize>, Vocabulary), ReaderError> {
+        let mut vocab = Vocabulary::new();
+        let emb = self
+            .read_archive(open_archive(&file)?, |key| Ok(vocab.intern(key)))
+            .map_err(|e| e.with_path(&file))?;
+
+        Ok((emb, vocab))
+    }
+}
+
+impl<K: EmbeddingKey> NpzEmbeddingReader<K> {
+    pub fn with_key_type() -> NpzEmbeddingReader<K>
+    where
+        K: FromStr,
+    {
+        NpzEmbeddingReader {
+            parse_key: from_str_key_parser(),
+        }
+    }
+
+    pub fn with_key_parser<F>(parse_key: F) -> NpzEmbeddingReader<K>
+    where
+        F: Fn(&str) -> Result<K, ReaderError> + Send + Sync + 'static,
+    {
+        NpzEmbeddingReader {
+            parse_key: Box::new(parse_key),
+        }
+    }
+
+    fn read_archive<R, F>(&self, archive: R, parse_key: F) -> Result<Embedding<K>, ReaderError>
+    where
+        R: Read + Seek,
+        F: FnMut(&str) -> Result<K, ReaderError>,
+    {
+        let mut archive = ZipArchive::new(archive).map_err(zip_error)?;
+
+        let vectors = npy::read_array(&mut archive.by_name("vectors.npy").map_err(zip_error)?)?;
+        let keys = npy::read_array(&mut archive.by_name("keys.npy").map_err(zip_error)?)?;
+
+        let keys: Vec<String> = match keys.data {
+            NpyData::Unicode(keys) => keys,
+            NpyData::Int(keys) => keys.iter().map(|k| k.to_string()).collect(),
+            NpyData::Float(_) => {
+                return Err(ReaderError::malformed("Keys should be strings or integers"))
             }
+        };
+
+        embedding_from_arrays(vectors, keys, parse_key)
+    }
+}
+
+impl<K: EmbeddingKey> EmbeddingReader for NpzEmbeddingReader<K> {
+    type K = K;
+
+    /// Zip archives can't be read sequentially, the whole input is buffered
+    /// in memory
+    fn read_from<R: BufRead>(&self, mut reader: R) -> Result<Embedding<K>, ReaderError> {
+        let mut content = Vec::new();
+        reader.read_to_end(&mut content)?;
 
-            let _ = emb.add_vector(node, vector.as_slice());
+        self.read_archive(io::Cursor::new(content), &self.parse_key)
+    }
+
+    fn read(&self, file: PathBuf) -> Result<Embedding<K>, ReaderError> {
+        self.read_archive(open_archive(&file)?, &self.parse_key)
+            .map_err(|e| e.with_path(&file))
+    }
+}
+
+fn open_archive(file: &Path) -> Result<io::BufReader<fs::File>, ReaderError> {
+    fs::File::open(file)
+        .map(io::BufReader::new)
+        .map_err(|e| ReaderError::from(e).with_path(file))
+}
+
+fn zip_error(e: ZipError) -> ReaderError {
+    match e {
+        ZipError::Io(e) => ReaderError::from(e),
+        ZipError::FileNotFound => {
+            ReaderError::malformed("Archive should contain vectors.npy and keys.npy")
         }
+        _ => ReaderError::malformed("Not a valid .npz archive"),
+    }
+}
 
-        Ok(emb)
+fn embedding_from_arrays<K, F>(
+    vectors: NpyArray,
+    keys: Vec<String>,
+    mut parse_key: F,
+) -> Result<Embedding<K>, ReaderError>
+where
+    K: EmbeddingKey,
+    F: FnMut(&str) -> Result<K, ReaderError>,
+{
+    if vectors.shape.len() != 2 {
+        return Err(ReaderError::malformed(
+            "Vectors should be a 2-dimensional matrix",
+        ));
     }
+
+    let (num_nodes, dim) = (vectors.shape[0], vectors.shape[1]);
+
+    let values = match vectors.data {
+        NpyData::Float(values) => values,
+        _ => return Err(ReaderError::malformed("Vectors should be floats")),
+    };
+
+    if keys.len() != num_nodes {
+        return Err(ReaderError::malformed(&format!(
+            "Expected {} keys, got {}",
+            num_nodes,
+            keys.len()
+        )));
+    }
+
+    let mut emb = Embedding::new(dim);
+    for (row, key) in keys.iter().enumerate() {
+        emb.add_vector(parse_key(key)?, &values[row * dim..(row + 1) * dim])?;
+    }
+
+    Ok(emb)
+}
+
+/// Parses the `num_nodes dim` header shared by the word2vec formats
+fn parse_header(header_raw: &str) -> Result<(usize, usize), ReaderError> {
+    let header: Vec<&str> = header_raw.split(' ').collect();
+
+    if header.len() != 2 {
+        return Err(ReaderError::malformed(
+            "Header should contain 2 elements: number of nodes and embedding dimension",
+        )
+        .at_line(1)
+        .with_token(header_raw));
+    }
+
+    let num_nodes: usize = parse_token(header[0], "int").map_err(|e| e.at_line(1))?;
+    let dim: usize = parse_token(header[1], "int").map_err(|e| e.at_line(1))?;
+
+    Ok((num_nodes, dim))
+}
+
+/// Parses a `key v1 ... vdim` row, also returning its tokens
+fn parse_row<'a, K>(
+    emb_map_raw: &'a str,
+    dim: usize,
+    parse_key: &KeyParser<K>,
+) -> Result<(K, Vec<f64>, Vec<&'a str>), ReaderError> {
+    let emb_map: Vec<&str> = emb_map_raw.split(' ').collect();
+
+    if emb_map.len() != (dim + 1) {
+        return Err(ReaderError::malformed("Not enough elements").with_token(emb_map_raw));
+    }
+
+    let node = parse_key(emb_map[0])?;
+    let mut vector: Vec<f64> = Vec::new();
+    for v in emb_map[1..].iter() {
+        vector.push(parse_token(v, "float")?);
+    }
+
+    Ok((node, vector, emb_map))
 }
 
 #[cfg(test)]
@@ -0,0 +1,113 @@
+/// Similarity metrics between embedding vectors
+use std::cmp::Ordering;
+
+use ndarray::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Metric {
+    /// Cosine of the angle between vectors, 0 if either of them is zero
+    Cosine = 0,
+    Dot = 1,
+    /// Euclidean distance, lower means more similar
+    Euclidean = 2,
+}
+
+impl Metric {
+    pub fn between(self, a: &[f64], b: &[f64]) -> f64 {
+        let a = ArrayView1::from(a);
+        let b = ArrayView1::from(b);
+
+        match self {
+            Metric::Cosine => cosine(a.dot(&b), a.dot(&a), b.dot(&b)),
+            Metric::Dot => a.dot(&b),
+            Metric::Euclidean => euclidean(a, b),
+        }
+    }
+
+    /// Dissimilarity derived from `between`, lower means more similar
+    pub(crate) fn distance(self, a: &[f64], b: &[f64]) -> f64 {
+        let score = self.between(a, b);
+
+        match self {
+            Metric::Cosine => 1.0 - score,
+            Metric::Dot => -score,
+            Metric::Euclidean => score,
+        }
+    }
+
+    /// Orders scores from the most to the least similar
+    fn rank(self, a: f64, b: f64) -> Ordering {
+        let ordering = match self {
+            Metric::Cosine | Metric::Dot => b.partial_cmp(&a),
+            Metric::Euclidean => a.partial_cmp(&b),
+        };
+
+        // NaN scores go last
+        ordering.unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+    }
+}
+
+fn cosine(dot: f64, norm_a_sq: f64, norm_b_sq: f64) -> f64 {
+    let norms = (norm_a_sq * norm_b_sq).sqrt();
+
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+fn euclidean(a: ArrayView1<f64>, b: ArrayView1<f64>) -> f64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Scores `query` against every row of `matrix`, with one matrix-vector
+/// product for the dot-based metrics
+pub(crate) fn scores(
+    matrix: ArrayView2<f64>,
+    query: ArrayView1<f64>,
+    metric: Metric,
+) -> Array1<f64> {
+    match metric {
+        Metric::Dot => matrix.dot(&query),
+        Metric::Cosine => {
+            let query_sq = query.dot(&query);
+            let mut scores = matrix.dot(&query);
+            for (score, row) in scores.iter_mut().zip(matrix.outer_iter()) {
+                *score = cosine(*score, row.dot(&row), query_sq);
+            }
+            scores
+        }
+        // Expanding to |row|^2 - 2 row.query + |query|^2 would cancel out
+        // the precision of distances between large vectors
+        Metric::Euclidean => matrix
+            .outer_iter()
+            .map(|row| euclidean(row, query))
+            .collect(),
+    }
+}
+
+/// Indices of the `k` best scores, most similar first. Ties keep the lower
+/// index first.
+pub(crate) fn top_k(scores: &[f64], k: usize, metric: Metric) -> Vec<usize> {
+    let compare = |a: &usize, b: &usize| metric.rank(scores[*a], scores[*b]).then(a.cmp(b));
+
+    let mut indices: Vec<usize> = (0..scores.len()).collect();
+    if k < indices.len() {
+        if k > 0 {
+            indices.select_nth_unstable_by(k - 1, compare);
+        }
+        indices.truncate(k);
+    }
+    indices.sort_by(compare);
+
+    indices
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/embedding/similarity_tests.rs"]
+mod similarity_tests;
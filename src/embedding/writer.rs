@@ -0,0 +1,170 @@
+/// Embedding file writer
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::embedding::model::*;
+use crate::embedding::npy;
+
+pub trait EmbeddingWriter<K: EmbeddingKey> {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error>;
+}
+
+/// Writes the word2vec text format: a `num_nodes dim` header followed by
+/// one `key v_0 v_1 ... v_n` row per vector, ordered by key.
+#[derive(Default)]
+pub struct W2VEmbeddingVectorsWriter {
+    precision: Option<usize>,
+}
+
+impl W2VEmbeddingVectorsWriter {
+    pub fn new() -> W2VEmbeddingVectorsWriter {
+        W2VEmbeddingVectorsWriter { precision: None }
+    }
+
+    /// Number of decimal places written. By default floats are written with
+    /// as many digits as needed to read back the exact same value.
+    pub fn set_precision(&mut self, precision: usize) {
+        self.precision = Some(precision);
+    }
+}
+
+impl<K: EmbeddingKey + Ord + Display> EmbeddingWriter<K> for W2VEmbeddingVectorsWriter {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error> {
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+
+        let mut rows = emb.vectors();
+        rows.sort_by(|a, b| a.0.cmp(b.0));
+
+        writeln!(writer, "{} {}", rows.len(), emb.dim())?;
+
+        for (key, vector) in rows {
+            write!(writer, "{}", key)?;
+
+            for v in vector.iter() {
+                match self.precision {
+                    Some(precision) => write!(writer, " {:.*}", precision, v)?,
+                    None => write!(writer, " {}", v)?,
+                }
+            }
+
+            writeln!(writer)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// Writes the word2vec binary format: a `num_nodes dim` text header
+/// followed by `key<space>`, `dim` little-endian f32 values and a newline
+/// per vector, ordered by key. Values are narrowed to f32.
+#[derive(Default)]
+pub struct W2VBinaryEmbeddingWriter {}
+
+impl W2VBinaryEmbeddingWriter {
+    pub fn new() -> W2VBinaryEmbeddingWriter {
+        W2VBinaryEmbeddingWriter {}
+    }
+}
+
+impl<K: EmbeddingKey + Ord + Display> EmbeddingWriter<K> for W2VBinaryEmbeddingWriter {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error> {
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+
+        let mut rows = emb.vectors();
+        rows.sort_by(|a, b| a.0.cmp(b.0));
+
+        writeln!(writer, "{} {}", rows.len(), emb.dim())?;
+
+        for (key, vector) in rows {
+            write!(writer, "{} ", key)?;
+
+            for v in vector.iter() {
+                writer.write_all(&(*v as f32).to_le_bytes())?;
+            }
+
+            writeln!(writer)?;
+        }
+
+        writer.flush()
+    }
+}
+
+/// Keys ordered by their value and the matching vectors as one row-major
+/// matrix
+fn sorted_rows<K: EmbeddingKey + Ord + Display>(emb: &Embedding<K>) -> (Vec<String>, Vec<f64>) {
+    let mut rows = emb.vectors();
+    rows.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut values: Vec<f64> = Vec::with_capacity(rows.len() * emb.dim());
+    for (_, vector) in rows.iter() {
+        values.extend_from_slice(vector);
+    }
+
+    (rows.iter().map(|(k, _)| k.to_string()).collect(), values)
+}
+
+/// Writes the vectors as a `num_nodes x dim` `.npy` matrix ordered by key,
+/// and the keys of the rows, one per line, to a companion text file.
+pub struct NpyEmbeddingWriter {
+    keys_file: PathBuf,
+}
+
+impl NpyEmbeddingWriter {
+    pub fn new(keys_file: PathBuf) -> NpyEmbeddingWriter {
+        NpyEmbeddingWriter { keys_file }
+    }
+}
+
+impl<K: EmbeddingKey + Ord + Display> EmbeddingWriter<K> for NpyEmbeddingWriter {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error> {
+        let (keys, values) = sorted_rows(emb);
+
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+        npy::write_matrix(&mut writer, keys.len(), emb.dim(), &values)?;
+        writer.flush()?;
+
+        let mut keys_writer = io::BufWriter::new(fs::File::create(&self.keys_file)?);
+        for key in keys.iter() {
+            writeln!(keys_writer, "{}", key)?;
+        }
+        keys_writer.flush()
+    }
+}
+
+/// Writes an uncompressed `.npz` archive, as `numpy.savez` does, holding the
+/// `vectors` matrix ordered by key and the matching `keys` as strings.
+#[derive(Default)]
+pub struct NpzEmbeddingWriter {}
+
+impl NpzEmbeddingWriter {
+    pub fn new() -> NpzEmbeddingWriter {
+        NpzEmbeddingWriter {}
+    }
+}
+
+impl<K: EmbeddingKey + Ord + Display> EmbeddingWriter<K> for NpzEmbeddingWriter {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error> {
+        let (keys, values) = sorted_rows(emb);
+
+        let mut zip = ZipWriter::new(io::BufWriter::new(fs::File::create(file)?));
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+        zip.start_file("vectors.npy", options)?;
+        npy::write_matrix(&mut zip, keys.len(), emb.dim(), &values)?;
+
+        zip.start_file("keys.npy", options)?;
+        npy::write_strings(&mut zip, &keys)?;
+
+        zip.finish()?.flush()
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/embedding/writer_tests.rs"]
+mod writer_tests;
@@ -0,0 +1,266 @@
+/// Errors shared by file readers
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::embedding::model::EmbeddingError;
+use crate::network::model::NetworkError;
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    FileNotFound,
+    Io(io::Error),
+    MalformedFileFormat(String),
+    InvalidNetwork(NetworkError),
+    InvalidEmbedding(EmbeddingError),
+    /// Snapshot written by a newer, unsupported format version
+    VersionMismatch {
+        found: u16,
+        supported: u16,
+    },
+    /// Number of rows differs from the one announced in the header
+    RowCountMismatch {
+        expected: usize,
+        found: usize,
+    },
+    /// Key that was already read earlier in the file
+    DuplicateKey,
+    /// NaN or infinite value
+    NonFiniteValue,
+}
+
+impl PartialEq for ErrorKind {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        match (self, other) {
+            (ErrorKind::FileNotFound, ErrorKind::FileNotFound) => true,
+            // `io::Error` can't be compared, its kind is the best we have
+            (ErrorKind::Io(a), ErrorKind::Io(b)) => a.kind() == b.kind(),
+            (ErrorKind::MalformedFileFormat(a), ErrorKind::MalformedFileFormat(b)) => a == b,
+            (ErrorKind::InvalidNetwork(a), ErrorKind::InvalidNetwork(b)) => a == b,
+            (ErrorKind::InvalidEmbedding(a), ErrorKind::InvalidEmbedding(b)) => a == b,
+            (
+                ErrorKind::VersionMismatch { found, supported },
+                ErrorKind::VersionMismatch {
+                    found: other_found,
+                    supported: other_supported,
+                },
+            ) => found == other_found && supported == other_supported,
+            (
+                ErrorKind::RowCountMismatch { expected, found },
+                ErrorKind::RowCountMismatch {
+                    expected: other_expected,
+                    found: other_found,
+                },
+            ) => expected == other_expected && found == other_found,
+            (ErrorKind::DuplicateKey, ErrorKind::DuplicateKey) => true,
+            (ErrorKind::NonFiniteValue, ErrorKind::NonFiniteValue) => true,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorKind::FileNotFound => write!(f, "File not found"),
+            ErrorKind::Io(e) => write!(f, "IO error: {}", e),
+            ErrorKind::MalformedFileFormat(msg) => write!(f, "{}", msg),
+            ErrorKind::InvalidNetwork(e) => write!(f, "Invalid network: {}", e),
+            ErrorKind::InvalidEmbedding(e) => write!(f, "Invalid embedding: {}", e),
+            ErrorKind::VersionMismatch { found, supported } => write!(
+                f,
+                "Unsupported format version {} (up to {} is supported)",
+                found, supported
+            ),
+            ErrorKind::RowCountMismatch { expected, found } => write!(
+                f,
+                "Header announces {} rows but {} were found",
+                expected, found
+            ),
+            ErrorKind::DuplicateKey => write!(f, "Duplicate key"),
+            ErrorKind::NonFiniteValue => write!(f, "Non-finite value"),
+        }
+    }
+}
+
+/// Reader failure together with where in the input it happened
+#[derive(Debug, PartialEq)]
+pub struct ReaderError {
+    kind: ErrorKind,
+    path: Option<PathBuf>,
+    line: Option<usize>,
+    token: Option<String>,
+}
+
+impl ReaderError {
+    pub fn new(kind: ErrorKind) -> ReaderError {
+        ReaderError {
+            kind,
+            path: None,
+            line: None,
+            token: None,
+        }
+    }
+
+    pub fn malformed(msg: &str) -> ReaderError {
+        ReaderError::new(ErrorKind::MalformedFileFormat(msg.to_string()))
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 1-based number of the offending line
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    pub(crate) fn with_path(mut self, path: &Path) -> ReaderError {
+        if self.path.is_none() {
+            self.path = Some(path.to_path_buf());
+        }
+        self
+    }
+
+    pub(crate) fn at_line(mut self, line: usize) -> ReaderError {
+        if self.line.is_none() {
+            self.line = Some(line);
+        }
+        self
+    }
+
+    pub(crate) fn with_token(mut self, token: &str) -> ReaderError {
+        self.token = Some(token.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{}:", line)?;
+        }
+        if self.path.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+
+        write!(f, "{}", self.kind)?;
+
+        if let Some(token) = &self.token {
+            write!(f, " (offending token: {:?})", token)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ReaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReaderError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => ReaderError::new(ErrorKind::FileNotFound),
+            _ => ReaderError::new(ErrorKind::Io(e)),
+        }
+    }
+}
+
+impl From<NetworkError> for ReaderError {
+    fn from(e: NetworkError) -> Self {
+        ReaderError::new(ErrorKind::InvalidNetwork(e))
+    }
+}
+
+impl From<EmbeddingError> for ReaderError {
+    fn from(e: EmbeddingError) -> Self {
+        ReaderError::new(ErrorKind::InvalidEmbedding(e))
+    }
+}
+
+/// Lines skipped while reading in lenient mode
+#[derive(Debug, Default, PartialEq)]
+pub struct ReadReport {
+    skipped: Vec<ReaderError>,
+}
+
+impl ReadReport {
+    pub fn new() -> ReadReport {
+        ReadReport { skipped: vec![] }
+    }
+
+    /// Errors of the skipped lines, each carrying its line number
+    pub fn skipped(&self) -> &[ReaderError] {
+        &self.skipped
+    }
+
+    pub fn num_skipped(&self) -> usize {
+        self.skipped.len()
+    }
+
+    pub(crate) fn with_path(self, path: &Path) -> ReadReport {
+        ReadReport {
+            skipped: self
+                .skipped
+                .into_iter()
+                .map(|e| e.with_path(path))
+                .collect(),
+        }
+    }
+
+    /// Records `err` if it concerns a single malformed line and fewer than
+    /// `max_skipped` lines were skipped so far; returns it otherwise. `None`
+    /// means strict mode, where nothing is skipped.
+    pub(crate) fn skip(
+        &mut self,
+        err: ReaderError,
+        max_skipped: Option<usize>,
+    ) -> Result<(), ReaderError> {
+        let is_line_error = matches!(
+            err.kind(),
+            ErrorKind::MalformedFileFormat(_)
+                | ErrorKind::InvalidNetwork(_)
+                | ErrorKind::InvalidEmbedding(_)
+                | ErrorKind::DuplicateKey
+                | ErrorKind::NonFiniteValue
+        );
+
+        match max_skipped {
+            Some(max) if is_line_error && self.skipped.len() < max => {
+                self.skipped.push(err);
+                Ok(())
+            }
+            _ => Err(err),
+        }
+    }
+}
+
+/// Parses a single token, `what` names the expected type in the error
+pub(crate) fn parse_token<T: FromStr>(token: &str, what: &str) -> Result<T, ReaderError> {
+    token
+        .parse()
+        .map_err(|_| ReaderError::malformed(&format!("Couldn't parse {}", what)).with_token(token))
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/error_tests.rs"]
+mod error_tests;
@@ -1,4 +1,9 @@
 /// Rusty nets - Fast and Reliable Network Embedding
 pub mod algorithm;
+pub mod compression;
 pub mod embedding;
+pub mod error;
 pub mod network;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod snapshot;
@@ -0,0 +1,261 @@
+/// GML network reader
+use std::io::BufRead;
+
+use crate::error::parse_token;
+use crate::network::model::*;
+use crate::network::reader::{NetworkReader, ReaderError};
+
+#[derive(Debug, PartialEq)]
+enum GmlValue {
+    /// Number or bare word
+    Atom(String),
+    Str(String),
+    List(Vec<GmlEntry>),
+}
+
+#[derive(Debug, PartialEq)]
+struct GmlEntry {
+    key: String,
+    value: GmlValue,
+    line: usize,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Atom(String),
+    Str(String),
+}
+
+fn tokenize(content: &str) -> Result<Vec<(Token, usize)>, ReaderError> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            _ if c.is_whitespace() => {}
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '[' => tokens.push((Token::Open, line)),
+            ']' => tokens.push((Token::Close, line)),
+            '"' => {
+                let start_line = line;
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            value.push(c);
+                        }
+                        None => {
+                            return Err(
+                                ReaderError::malformed("Unterminated string").at_line(start_line)
+                            )
+                        }
+                    }
+                }
+                tokens.push((Token::Str(value), start_line));
+            }
+            _ => {
+                let mut value = c.to_string();
+                while let Some(c) = chars.peek() {
+                    if c.is_whitespace() || *c == '[' || *c == ']' || *c == '"' {
+                        break;
+                    }
+                    value.push(*c);
+                    chars.next();
+                }
+                tokens.push((Token::Atom(value), line));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Deepest list nesting accepted, so hostile input can't overflow the stack
+const MAX_DEPTH: usize = 64;
+
+/// Parses `key value` pairs until the closing bracket (or the end of input
+/// for the top level, at depth 0)
+fn parse_list<I>(tokens: &mut I, depth: usize) -> Result<Vec<GmlEntry>, ReaderError>
+where
+    I: Iterator<Item = (Token, usize)>,
+{
+    let top_level = depth == 0;
+    let mut entries = Vec::new();
+
+    loop {
+        let (key, line) = match tokens.next() {
+            Some((Token::Atom(key), line)) => (key, line),
+            Some((Token::Close, _)) if !top_level => return Ok(entries),
+            None if top_level => return Ok(entries),
+            None => return Err(ReaderError::malformed("Unexpected end of file")),
+            Some((Token::Close, line)) => {
+                return Err(ReaderError::malformed("Unmatched ]").at_line(line))
+            }
+            Some((Token::Open, line)) => {
+                return Err(ReaderError::malformed("Expected key")
+                    .with_token("[")
+                    .at_line(line))
+            }
+            Some((Token::Str(s), line)) => {
+                return Err(ReaderError::malformed("Expected key")
+                    .with_token(&s)
+                    .at_line(line))
+            }
+        };
+
+        let value = match tokens.next() {
+            Some((Token::Open, line)) if depth == MAX_DEPTH => {
+                return Err(ReaderError::malformed("Lists nested too deeply").at_line(line))
+            }
+            Some((Token::Open, _)) => GmlValue::List(parse_list(tokens, depth + 1)?),
+            Some((Token::Atom(a), _)) => GmlValue::Atom(a),
+            Some((Token::Str(s), _)) => GmlValue::Str(s),
+            Some((Token::Close, line)) => {
+                return Err(ReaderError::malformed("Missing value")
+                    .with_token(&key)
+                    .at_line(line))
+            }
+            None => {
+                return Err(ReaderError::malformed("Missing value")
+                    .with_token(&key)
+                    .at_line(line))
+            }
+        };
+
+        entries.push(GmlEntry { key, value, line });
+    }
+}
+
+fn find<'a>(entries: &'a [GmlEntry], key: &str) -> Option<&'a GmlEntry> {
+    entries.iter().find(|e| e.key == key)
+}
+
+fn atom<'a>(entries: &'a [GmlEntry], key: &str, line: usize) -> Result<&'a str, ReaderError> {
+    match find(entries, key) {
+        Some(GmlEntry {
+            value: GmlValue::Atom(a),
+            ..
+        }) => Ok(a),
+        Some(e) => {
+            Err(ReaderError::malformed(&format!("Expected a number for {}", key)).at_line(e.line))
+        }
+        None => Err(ReaderError::malformed(&format!("Missing {}", key)).at_line(line)),
+    }
+}
+
+/// Reads the `graph [ ... ]` of a GML file. Node ids are integers and node
+/// labels are stored as network labels. Edge weights are taken from the
+/// chosen numeric attribute, defaulting to 1.0 when it's absent.
+pub struct GmlReader {
+    weight_attribute: String,
+}
+
+impl Default for GmlReader {
+    fn default() -> Self {
+        GmlReader::new()
+    }
+}
+
+impl GmlReader {
+    pub fn new() -> GmlReader {
+        GmlReader {
+            weight_attribute: "weight".to_string(),
+        }
+    }
+
+    pub fn set_weight_attribute(&mut self, name: &str) {
+        self.weight_attribute = name.to_string();
+    }
+
+    fn read_graph<R: BufRead>(&self, mut reader: R) -> Result<Network<usize, f64>, ReaderError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let entries = parse_list(&mut tokenize(&content)?.into_iter(), 0)?;
+
+        let (graph, graph_line) = match find(&entries, "graph") {
+            Some(GmlEntry {
+                value: GmlValue::List(graph),
+                line,
+                ..
+            }) => (graph, *line),
+            _ => return Err(ReaderError::malformed("Missing graph")),
+        };
+
+        let directed = match find(graph, "directed") {
+            Some(_) => atom(graph, "directed", graph_line)? == "1",
+            None => false,
+        };
+        let mut net = Network::new(directed);
+
+        for entry in graph.iter() {
+            let attributes = match &entry.value {
+                GmlValue::List(attributes) => attributes,
+                _ => continue,
+            };
+            let line = entry.line;
+
+            match entry.key.as_str() {
+                "node" => {
+                    let id = atom(attributes, "id", line)?;
+                    let node: usize = parse_token(id, "int").map_err(|e| e.at_line(line))?;
+                    net.add_node(node);
+
+                    if let Some(GmlEntry {
+                        value: GmlValue::Str(label),
+                        ..
+                    }) = find(attributes, "label")
+                    {
+                        net.set_node_label(node, label);
+                    }
+                }
+                "edge" => {
+                    let from: usize = parse_token(atom(attributes, "source", line)?, "int")
+                        .map_err(|e| e.at_line(line))?;
+                    let to: usize = parse_token(atom(attributes, "target", line)?, "int")
+                        .map_err(|e| e.at_line(line))?;
+                    let weight: f64 = match find(attributes, &self.weight_attribute) {
+                        Some(_) => {
+                            parse_token(atom(attributes, &self.weight_attribute, line)?, "float")
+                                .map_err(|e| e.at_line(line))?
+                        }
+                        None => 1.0,
+                    };
+
+                    net.add_edge(from, to, weight)
+                        .map_err(|e| ReaderError::from(e).at_line(line))?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(net)
+    }
+}
+
+impl NetworkReader for GmlReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        self.read_graph(reader)
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/gml_tests.rs"]
+mod gml_tests;
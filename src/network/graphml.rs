@@ -0,0 +1,177 @@
+/// GraphML network reader
+use std::io::BufRead;
+use std::path::PathBuf;
+
+use roxmltree::{Document, Node};
+
+use crate::compression;
+use crate::error::parse_token;
+use crate::network::model::*;
+use crate::network::reader::{NetworkReader, ReaderError};
+use crate::network::vocabulary::Vocabulary;
+
+/// Reads the `<graph>` of a GraphML file. Edge weights are taken from the
+/// `<data>` of the key with the chosen `attr.name`, falling back to the
+/// key's `<default>` and then to 1.0. Directedness follows `edgedefault`;
+/// edges marked `directed="false"` in a directed graph are added in both
+/// directions.
+pub struct GraphMLReader {
+    weight_attribute: String,
+}
+
+impl Default for GraphMLReader {
+    fn default() -> Self {
+        GraphMLReader::new()
+    }
+}
+
+impl GraphMLReader {
+    pub fn new() -> GraphMLReader {
+        GraphMLReader {
+            weight_attribute: "weight".to_string(),
+        }
+    }
+
+    pub fn set_weight_attribute(&mut self, name: &str) {
+        self.weight_attribute = name.to_string();
+    }
+
+    /// Reads a network with arbitrary textual node ids. The ids are interned
+    /// into consecutive integers, the returned vocabulary maps between both.
+    pub fn read_interned(
+        &self,
+        file: PathBuf,
+    ) -> Result<(Network<usize, f64>, Vocabulary), ReaderError> {
+        let mut vocab = Vocabulary::new();
+        let net = compression::read_file(&file, |reader| {
+            self.read_document(reader, |node| Ok(vocab.intern(node)))
+        })?;
+
+        Ok((net, vocab))
+    }
+
+    fn read_document<R, F>(
+        &self,
+        mut reader: R,
+        mut parse_node: F,
+    ) -> Result<Network<usize, f64>, ReaderError>
+    where
+        R: BufRead,
+        F: FnMut(&str) -> Result<usize, ReaderError>,
+    {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let doc = Document::parse(&content).map_err(|e| {
+            ReaderError::malformed(&format!("Invalid XML: {}", e)).at_line(e.pos().row as usize)
+        })?;
+
+        let line_of = |node: &Node| doc.text_pos_at(node.range().start).row as usize;
+
+        let weight_key = doc
+            .descendants()
+            .find(|n| {
+                n.tag_name().name() == "key"
+                    && n.attribute("attr.name") == Some(self.weight_attribute.as_str())
+                    && matches!(n.attribute("for"), Some("edge") | Some("all") | None)
+            })
+            .map(|key| -> Result<(&str, f64), ReaderError> {
+                let id = key.attribute("id").ok_or_else(|| {
+                    ReaderError::malformed("Key without id").at_line(line_of(&key))
+                })?;
+                let default = match key.children().find(|n| n.tag_name().name() == "default") {
+                    Some(d) => parse_token(d.text().unwrap_or("").trim(), "float")
+                        .map_err(|e| e.at_line(line_of(&d)))?,
+                    None => 1.0,
+                };
+
+                Ok((id, default))
+            })
+            .transpose()?;
+
+        let graph = doc
+            .descendants()
+            .find(|n| n.tag_name().name() == "graph")
+            .ok_or_else(|| ReaderError::malformed("Missing graph element"))?;
+
+        let directed = graph.attribute("edgedefault") != Some("undirected");
+        let mut net = Network::new(directed);
+
+        for element in graph.children().filter(|n| n.is_element()) {
+            let line = line_of(&element);
+
+            match element.tag_name().name() {
+                "node" => {
+                    let id = element
+                        .attribute("id")
+                        .ok_or_else(|| ReaderError::malformed("Node without id").at_line(line))?;
+                    net.add_node(parse_node(id).map_err(|e| e.at_line(line))?);
+                }
+                "edge" => {
+                    let (source, target) =
+                        match (element.attribute("source"), element.attribute("target")) {
+                            (Some(s), Some(t)) => (s, t),
+                            _ => {
+                                return Err(ReaderError::malformed("Edge without source or target")
+                                    .at_line(line))
+                            }
+                        };
+
+                    let from = parse_node(source).map_err(|e| e.at_line(line))?;
+                    let to = parse_node(target).map_err(|e| e.at_line(line))?;
+
+                    let weight = match weight_key {
+                        Some((key_id, default)) => {
+                            match element.children().find(|n| {
+                                n.tag_name().name() == "data" && n.attribute("key") == Some(key_id)
+                            }) {
+                                Some(data) => {
+                                    parse_token(data.text().unwrap_or("").trim(), "float")
+                                        .map_err(|e| e.at_line(line_of(&data)))?
+                                }
+                                None => default,
+                            }
+                        }
+                        None => 1.0,
+                    };
+
+                    match element.attribute("directed") {
+                        Some("true") if !directed => {
+                            return Err(ReaderError::malformed(
+                                "Directed edges are not supported in undirected graphs",
+                            )
+                            .at_line(line))
+                        }
+                        Some("false") if directed => {
+                            net.add_edge(from, to, weight)
+                                .map_err(|e| ReaderError::from(e).at_line(line))?;
+                            if from != to {
+                                net.add_edge(to, from, weight)
+                                    .map_err(|e| ReaderError::from(e).at_line(line))?;
+                            }
+                        }
+                        _ => {
+                            net.add_edge(from, to, weight)
+                                .map_err(|e| ReaderError::from(e).at_line(line))?;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(net)
+    }
+}
+
+impl NetworkReader for GraphMLReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        self.read_document(reader, |node| parse_token(node, "int"))
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/graphml_tests.rs"]
+mod graphml_tests;
@@ -0,0 +1,315 @@
+/// METIS graph reader and writer
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use petgraph::visit::EdgeRef;
+
+use crate::error::parse_token;
+use crate::network::model::*;
+use crate::network::reader::{NetworkReader, ReaderError};
+use crate::network::writer::NetworkWriter;
+
+/// Reads METIS graph files: a `n m [fmt [ncon]]` header followed by the
+/// adjacency line of each of the `n` vertices. Vertices are numbered from 1
+/// in the file and from 0 in the network, which is always undirected.
+/// Vertex sizes and weights are skipped, edge weights (if present) become
+/// edge data, 1.0 otherwise. Both endpoints have to list every edge with the
+/// same weight, and self-loops are rejected.
+#[derive(Default)]
+pub struct MetisReader {}
+
+impl MetisReader {
+    pub fn new() -> MetisReader {
+        MetisReader {}
+    }
+
+    fn read_lines<R: BufRead>(&self, mut reader: R) -> Result<Network<usize, f64>, ReaderError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        // Empty lines are vertices without neighbours, only comments are
+        // skipped
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.starts_with('%'));
+
+        let (header_idx, header_raw) = match lines.next() {
+            Some(header) => header,
+            None => return Err(ReaderError::malformed("Missing header").at_line(1)),
+        };
+        let header = parse_header(header_raw).map_err(|e| e.at_line(header_idx + 1))?;
+
+        // Every vertex has a line, so the header can't make us allocate more
+        // nodes than there are lines
+        let lines: Vec<(usize, &str)> = lines.collect();
+        if lines.len() < header.num_vertices {
+            return Err(ReaderError::malformed(&format!(
+                "Expected {} vertices, got {}",
+                header.num_vertices,
+                lines.len()
+            )));
+        }
+
+        let mut net = Network::new(false);
+        for node in 0..header.num_vertices {
+            net.add_node(node);
+        }
+
+        // Edges listed by their lower vertex, waiting for the higher one
+        let mut pending = PendingEdges::new();
+        let mut num_vertices = 0;
+        for (idx, line) in lines {
+            if num_vertices == header.num_vertices {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Err(ReaderError::malformed(&format!(
+                    "Expected {} vertices",
+                    header.num_vertices
+                ))
+                .at_line(idx + 1));
+            }
+
+            self.read_vertex(line, idx + 1, num_vertices, &header, &mut pending, &mut net)
+                .map_err(|e| e.at_line(idx + 1))?;
+            num_vertices += 1;
+        }
+
+        if let Some((&(vertex, neighbour), entries)) = pending
+            .iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .min_by_key(|(_, entries)| entries[0].1)
+        {
+            return Err(one_sided(vertex, neighbour).at_line(entries[0].1));
+        }
+
+        if net.num_edges() != header.num_edges {
+            return Err(ReaderError::malformed(&format!(
+                "Expected {} edges, got {}",
+                header.num_edges,
+                net.num_edges()
+            )));
+        }
+
+        Ok(net)
+    }
+
+    fn read_vertex(
+        &self,
+        line: &str,
+        line_no: usize,
+        vertex: usize,
+        header: &MetisHeader,
+        pending: &mut PendingEdges,
+        net: &mut Network<usize, f64>,
+    ) -> Result<(), ReaderError> {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        let skip = header.has_vertex_sizes as usize + header.num_vertex_weights;
+        if tokens.len() < skip {
+            return Err(ReaderError::malformed("Not enough elements").with_token(line));
+        }
+
+        let step = if header.has_edge_weights { 2 } else { 1 };
+        if (tokens.len() - skip) % step != 0 {
+            return Err(
+                ReaderError::malformed("Every neighbour should have a weight").with_token(line),
+            );
+        }
+
+        for entry in tokens[skip..].chunks(step) {
+            let neighbour: usize = parse_token(entry[0], "int")?;
+            if neighbour == 0 || neighbour > header.num_vertices {
+                return Err(ReaderError::malformed("Vertex out of range").with_token(entry[0]));
+            }
+
+            let weight: f64 = match entry.get(1) {
+                Some(w) => parse_token::<usize>(w, "int")? as f64,
+                None => 1.0,
+            };
+
+            // Every edge is listed by both of its endpoints, it's added when
+            // the first one lists it and checked off by the second one
+            let neighbour = neighbour - 1;
+            if vertex == neighbour {
+                return Err(
+                    ReaderError::malformed("Self-loops are not allowed").with_token(entry[0])
+                );
+            } else if vertex < neighbour {
+                net.add_edge(vertex, neighbour, weight)?;
+                pending
+                    .entry((vertex, neighbour))
+                    .or_default()
+                    .push((weight, line_no));
+            } else {
+                let listed = pending.get_mut(&(neighbour, vertex)).and_then(|e| e.pop());
+                match listed {
+                    None => return Err(one_sided(vertex, neighbour).with_token(entry[0])),
+                    Some((listed_weight, _)) if listed_weight != weight => {
+                        return Err(ReaderError::malformed(&format!(
+                            "Vertex {} lists this edge with weight {}",
+                            neighbour + 1,
+                            listed_weight
+                        ))
+                        .with_token(entry[1]))
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Weight and line of the edges listed by one endpoint only so far
+type PendingEdges = HashMap<(usize, usize), Vec<(f64, usize)>>;
+
+/// Error for an edge listed by (0-based) `vertex` but not by `neighbour`
+fn one_sided(vertex: usize, neighbour: usize) -> ReaderError {
+    ReaderError::malformed(&format!(
+        "Vertex {} doesn't list vertex {}",
+        neighbour + 1,
+        vertex + 1
+    ))
+}
+
+struct MetisHeader {
+    num_vertices: usize,
+    num_edges: usize,
+    has_vertex_sizes: bool,
+    num_vertex_weights: usize,
+    has_edge_weights: bool,
+}
+
+fn parse_header(header_raw: &str) -> Result<MetisHeader, ReaderError> {
+    let header: Vec<&str> = header_raw.split_whitespace().collect();
+
+    if header.len() < 2 || header.len() > 4 {
+        return Err(ReaderError::malformed(
+            "Header should contain 2 to 4 elements: vertices, edges, fmt and ncon",
+        )
+        .with_token(header_raw));
+    }
+
+    let num_vertices: usize = parse_token(header[0], "int")?;
+    let num_edges: usize = parse_token(header[1], "int")?;
+
+    let fmt = header.get(2).cloned().unwrap_or("000");
+    if fmt.len() > 3 || fmt.chars().any(|c| c != '0' && c != '1') {
+        return Err(
+            ReaderError::malformed("Format should be up to 3 binary digits").with_token(fmt),
+        );
+    }
+    let fmt = format!("{:0>3}", fmt);
+    let flag = |idx: usize| fmt.as_bytes()[idx] == b'1';
+
+    let num_vertex_weights = match (flag(1), header.get(3)) {
+        (true, Some(ncon)) => parse_token(ncon, "int")?,
+        (true, None) => 1,
+        (false, _) => 0,
+    };
+
+    Ok(MetisHeader {
+        num_vertices,
+        num_edges,
+        has_vertex_sizes: flag(0),
+        num_vertex_weights,
+        has_edge_weights: flag(2),
+    })
+}
+
+impl NetworkReader for MetisReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        self.read_lines(reader)
+    }
+}
+
+/// Writes METIS graph files. The network should be undirected and without
+/// self-loops; node ids are written as 1-based vertex numbers and missing ids
+/// as vertices without neighbours. Weights should be positive integers.
+pub struct MetisWriter {
+    write_weights: bool,
+}
+
+impl Default for MetisWriter {
+    fn default() -> Self {
+        MetisWriter::new()
+    }
+}
+
+impl MetisWriter {
+    pub fn new() -> MetisWriter {
+        MetisWriter {
+            write_weights: true,
+        }
+    }
+
+    pub fn set_write_weights(&mut self, write_weights: bool) {
+        self.write_weights = write_weights;
+    }
+}
+
+impl NetworkWriter<usize, f64> for MetisWriter {
+    fn write(&self, net: &Network<usize, f64>, file: PathBuf) -> Result<(), io::Error> {
+        let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+
+        if net.is_directed() {
+            return Err(invalid("METIS graphs should be undirected"));
+        }
+
+        let graph = net.graph();
+        let num_vertices = net.nodes().into_iter().max().map_or(0, |max| max + 1);
+
+        let mut adjacency: Vec<Vec<(usize, f64)>> = vec![Vec::new(); num_vertices];
+        for e in graph.edge_references() {
+            let (from, to) = (graph[e.source()], graph[e.target()]);
+            let weight = *e.weight();
+
+            if from == to {
+                return Err(invalid("METIS graphs can't contain self-loops"));
+            }
+            if self.write_weights && (weight < 1.0 || weight.fract() != 0.0) {
+                return Err(invalid("METIS edge weights should be positive integers"));
+            }
+
+            adjacency[from].push((to, weight));
+        }
+
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+
+        if self.write_weights {
+            writeln!(writer, "{} {} 001", num_vertices, net.num_edges())?;
+        } else {
+            writeln!(writer, "{} {}", num_vertices, net.num_edges())?;
+        }
+
+        for neighbours in adjacency.iter() {
+            let entries: Vec<String> = neighbours
+                .iter()
+                .map(|(to, weight)| {
+                    if self.write_weights {
+                        format!("{} {}", to + 1, weight)
+                    } else {
+                        (to + 1).to_string()
+                    }
+                })
+                .collect();
+
+            writeln!(writer, "{}", entries.join(" "))?;
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/metis_tests.rs"]
+mod metis_tests;
@@ -0,0 +1,300 @@
+/// Node centrality measures
+use std::collections::{HashMap, VecDeque};
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+use rand::prelude::*;
+use rand::seq::index;
+
+use crate::network::model::{Network, NetworkNode};
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, PartialEq)]
+pub enum MetricsError {
+    InvalidParameter(String),
+    NotConverged(String),
+}
+
+/// Weighted PageRank. Edge data is used as the transition weight and
+/// dangling nodes redistribute their rank according to the personalization
+/// vector (uniform if none is given).
+pub fn pagerank<N: NetworkNode>(
+    net: &Network<N, f64>,
+    damping: f64,
+    personalization: Option<&HashMap<N, f64>>,
+) -> Result<HashMap<N, f64>, MetricsError> {
+    if !(0.0..1.0).contains(&damping) {
+        return Err(MetricsError::InvalidParameter(format!(
+            "Damping factor should be in [0, 1). Got: {}",
+            damping
+        )));
+    }
+
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    if n == 0 {
+        return Ok(HashMap::new());
+    }
+
+    let teleport: Vec<f64> = match personalization {
+        None => vec![1.0 / n as f64; n],
+        Some(p) => {
+            let raw: Vec<f64> = graph
+                .node_indices()
+                .map(|nx| *p.get(&graph[nx]).unwrap_or(&0.0))
+                .collect();
+            let total: f64 = raw.iter().sum();
+
+            if total <= 0.0 || raw.iter().any(|v| *v < 0.0) {
+                return Err(MetricsError::InvalidParameter(
+                    "Personalization should be non-negative with a positive sum".to_string(),
+                ));
+            }
+
+            raw.iter().map(|v| v / total).collect()
+        }
+    };
+
+    let out_weight: Vec<f64> = graph
+        .node_indices()
+        .map(|nx| graph.edges(nx).map(|e| *e.weight()).sum())
+        .collect();
+
+    let mut rank = teleport.clone();
+
+    for _ in 0..MAX_ITERATIONS {
+        let dangling_rank: f64 = graph
+            .node_indices()
+            .filter(|nx| out_weight[nx.index()] == 0.0)
+            .map(|nx| rank[nx.index()])
+            .sum();
+
+        let mut next: Vec<f64> = teleport
+            .iter()
+            .map(|t| (1.0 - damping) * t + damping * dangling_rank * t)
+            .collect();
+
+        for e in graph.edge_references() {
+            let from = e.source().index();
+            if out_weight[from] != 0.0 {
+                next[e.target().index()] += damping * rank[from] * e.weight() / out_weight[from];
+            }
+        }
+
+        let err: f64 = next
+            .iter()
+            .zip(rank.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        rank = next;
+
+        if err < n as f64 * TOLERANCE {
+            return Ok(to_node_map(net, &rank));
+        }
+    }
+
+    Err(MetricsError::NotConverged(format!(
+        "PageRank did not converge in {} iterations",
+        MAX_ITERATIONS
+    )))
+}
+
+/// Betweenness centrality (hop based), normalized by `(n - 1)(n - 2)`.
+/// Only `num_samples` randomly chosen source nodes are used and the result
+/// is extrapolated; the computation is exact if `num_samples >= n`.
+pub fn approx_betweenness<N: NetworkNode, E: Copy>(
+    net: &Network<N, E>,
+    num_samples: usize,
+    seed: u64,
+) -> HashMap<N, f64> {
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    let mut centrality = vec![0.0; n];
+
+    let sources: Vec<usize> = if num_samples >= n {
+        (0..n).collect()
+    } else {
+        let mut rng = StdRng::seed_from_u64(seed);
+        index::sample(&mut rng, n, num_samples).into_vec()
+    };
+
+    for s in sources.iter() {
+        accumulate_dependencies(net, NodeIndex::new(*s), &mut centrality);
+    }
+
+    if n > 2 && !sources.is_empty() {
+        let scale = n as f64 / sources.len() as f64 / ((n - 1) * (n - 2)) as f64;
+        for c in centrality.iter_mut() {
+            *c *= scale;
+        }
+    }
+
+    to_node_map(net, &centrality)
+}
+
+// Single-source step of Brandes' algorithm
+fn accumulate_dependencies<N: NetworkNode, E: Copy>(
+    net: &Network<N, E>,
+    source: NodeIndex<u32>,
+    centrality: &mut [f64],
+) {
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    let mut order: Vec<NodeIndex<u32>> = vec![];
+    let mut predecessors: Vec<Vec<NodeIndex<u32>>> = vec![vec![]; n];
+    let mut num_paths = vec![0.0; n];
+    let mut dist: Vec<Option<usize>> = vec![None; n];
+
+    num_paths[source.index()] = 1.0;
+    dist[source.index()] = Some(0);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(source);
+
+    while let Some(v) = queue.pop_front() {
+        order.push(v);
+        let dv = dist[v.index()].unwrap();
+
+        for w in graph.neighbors(v) {
+            if dist[w.index()].is_none() {
+                dist[w.index()] = Some(dv + 1);
+                queue.push_back(w);
+            }
+            if dist[w.index()] == Some(dv + 1) {
+                num_paths[w.index()] += num_paths[v.index()];
+                predecessors[w.index()].push(v);
+            }
+        }
+    }
+
+    let mut dependency = vec![0.0; n];
+    while let Some(w) = order.pop() {
+        for v in predecessors[w.index()].iter() {
+            dependency[v.index()] +=
+                num_paths[v.index()] / num_paths[w.index()] * (1.0 + dependency[w.index()]);
+        }
+        if w != source {
+            centrality[w.index()] += dependency[w.index()];
+        }
+    }
+}
+
+/// Closeness centrality (hop based) using distances *to* each node. Nodes
+/// that are not reachable from everywhere are scaled by the fraction of
+/// nodes that can reach them (Wasserman and Faust).
+pub fn closeness<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> HashMap<N, f64> {
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    let centrality: Vec<f64> = graph
+        .node_indices()
+        .map(|nx| {
+            let dist = bfs_distances(net, nx, Direction::Incoming);
+            let reachable: Vec<usize> = dist.iter().filter_map(|d| *d).collect();
+            let total: usize = reachable.iter().sum();
+
+            if total == 0 || n < 2 {
+                return 0.0;
+            }
+
+            let others = (reachable.len() - 1) as f64;
+            (others / total as f64) * (others / (n - 1) as f64)
+        })
+        .collect();
+
+    to_node_map(net, &centrality)
+}
+
+fn bfs_distances<N: NetworkNode, E: Copy>(
+    net: &Network<N, E>,
+    source: NodeIndex<u32>,
+    dir: Direction,
+) -> Vec<Option<usize>> {
+    let graph = net.graph();
+    let mut dist: Vec<Option<usize>> = vec![None; graph.node_count()];
+
+    dist[source.index()] = Some(0);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(source);
+
+    while let Some(v) = queue.pop_front() {
+        let dv = dist[v.index()].unwrap();
+        for w in graph.neighbors_directed(v, dir) {
+            if dist[w.index()].is_none() {
+                dist[w.index()] = Some(dv + 1);
+                queue.push_back(w);
+            }
+        }
+    }
+
+    dist
+}
+
+/// Weighted eigenvector centrality computed by power iteration. For directed
+/// networks a node's score is based on the scores of nodes pointing to it.
+pub fn eigenvector<N: NetworkNode>(net: &Network<N, f64>) -> Result<HashMap<N, f64>, MetricsError> {
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    if n == 0 {
+        return Ok(HashMap::new());
+    }
+
+    let mut scores = vec![1.0 / n as f64; n];
+
+    for _ in 0..MAX_ITERATIONS {
+        // Start from the previous scores, i.e. iterate with (A + I), which
+        // keeps the iteration from oscillating on bipartite networks.
+        let mut next = scores.clone();
+
+        for e in graph.edge_references() {
+            next[e.target().index()] += scores[e.source().index()] * e.weight();
+        }
+
+        let norm = next.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm == 0.0 {
+            return Err(MetricsError::NotConverged(
+                "Eigenvector centrality vanished".to_string(),
+            ));
+        }
+        for v in next.iter_mut() {
+            *v /= norm;
+        }
+
+        let err: f64 = next
+            .iter()
+            .zip(scores.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        scores = next;
+
+        if err < n as f64 * TOLERANCE {
+            return Ok(to_node_map(net, &scores));
+        }
+    }
+
+    Err(MetricsError::NotConverged(format!(
+        "Eigenvector centrality did not converge in {} iterations",
+        MAX_ITERATIONS
+    )))
+}
+
+fn to_node_map<N: NetworkNode, E: Copy>(net: &Network<N, E>, values: &[f64]) -> HashMap<N, f64> {
+    let graph = net.graph();
+
+    graph
+        .node_indices()
+        .map(|nx| (graph[nx], values[nx.index()]))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/metrics_tests.rs"]
+mod metrics_tests;
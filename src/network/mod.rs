@@ -1,3 +1,13 @@
 /// Network data structure related code
+pub mod gml;
+pub mod graphml;
+pub mod metis;
+pub mod metrics;
 pub mod model;
+pub mod mtx;
+pub mod pajek;
+pub mod paths;
 pub mod reader;
+pub mod stats;
+pub mod vocabulary;
+pub mod writer;
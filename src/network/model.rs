@@ -1,14 +1,105 @@
 /// Network data structure
 use std::cmp::Eq;
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Debug;
 use std::hash::Hash;
+use std::ops::Add;
 
+use ndarray::prelude::*;
 use petgraph::graph::{EdgeIndex, NodeIndex};
 use petgraph::Graph;
 
-pub trait NetworkNode: Eq + Hash + Copy {}
+pub trait NetworkNode: Eq + Hash + Copy + Debug {}
 
-impl<T> NetworkNode for T where T: Eq + Hash + Copy {}
+impl<T> NetworkNode for T where T: Eq + Hash + Copy + Debug {}
+
+#[derive(Debug, PartialEq)]
+pub enum NetworkError {
+    DuplicateEdge(String),
+    SelfLoop(String),
+    WrongFeatureDimension(String),
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkError::WrongFeatureDimension(msg) => {
+                write!(f, "Wrong feature dimension ({})", msg)
+            }
+            NetworkError::DuplicateEdge(msg) | NetworkError::SelfLoop(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+/// Attribute vector of a single node
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodeFeatures {
+    Dense(Array1<f64>),
+    Sparse {
+        dim: usize,
+        entries: Vec<(usize, f64)>,
+    },
+}
+
+impl NodeFeatures {
+    pub fn dim(&self) -> usize {
+        match self {
+            NodeFeatures::Dense(v) => v.dim(),
+            NodeFeatures::Sparse { dim, .. } => *dim,
+        }
+    }
+
+    pub fn to_dense(&self) -> Array1<f64> {
+        match self {
+            NodeFeatures::Dense(v) => v.clone(),
+            NodeFeatures::Sparse { dim, entries } => {
+                let mut v = Array1::zeros(*dim);
+                for (idx, value) in entries.iter() {
+                    v[*idx] = *value;
+                }
+                v
+            }
+        }
+    }
+}
+
+/// What to do when an edge is added between an already connected pair
+#[derive(Debug, Clone, Copy)]
+pub enum MultiEdgePolicy<E> {
+    KeepAll,
+    Merge(fn(E, E) -> E),
+    Reject,
+}
+
+impl<E> MultiEdgePolicy<E> {
+    /// Replaces the data of the existing edge with the new one
+    pub fn last() -> MultiEdgePolicy<E> {
+        MultiEdgePolicy::Merge(|_, new| new)
+    }
+}
+
+impl<E: Copy + PartialOrd + Add<Output = E>> MultiEdgePolicy<E> {
+    pub fn sum() -> MultiEdgePolicy<E> {
+        MultiEdgePolicy::Merge(|old, new| old + new)
+    }
+
+    pub fn max() -> MultiEdgePolicy<E> {
+        MultiEdgePolicy::Merge(|old, new| if new > old { new } else { old })
+    }
+
+    pub fn min() -> MultiEdgePolicy<E> {
+        MultiEdgePolicy::Merge(|old, new| if new < old { new } else { old })
+    }
+}
+
+/// What to do when an edge is added from a node to itself
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelfLoopPolicy {
+    Keep,
+    Drop,
+    Error,
+}
 
 #[derive(Debug)]
 pub struct Network<N: NetworkNode, E: Copy> {
@@ -16,6 +107,12 @@ pub struct Network<N: NetworkNode, E: Copy> {
     nodes: HashMap<N, NodeIndex<u32>>,
     edges: HashMap<(N, N), Vec<EdgeIndex<u32>>>,
     is_directed: bool,
+    num_self_loops: usize,
+    multi_edge_policy: MultiEdgePolicy<E>,
+    self_loop_policy: SelfLoopPolicy,
+    feature_dim: Option<usize>,
+    features: HashMap<N, NodeFeatures>,
+    labels: HashMap<N, String>,
 }
 
 impl<N: NetworkNode, E: Copy> Network<N, E> {
@@ -25,9 +122,23 @@ impl<N: NetworkNode, E: Copy> Network<N, E> {
             nodes: HashMap::new(),
             edges: HashMap::new(),
             is_directed: directed,
+            num_self_loops: 0,
+            multi_edge_policy: MultiEdgePolicy::KeepAll,
+            self_loop_policy: SelfLoopPolicy::Keep,
+            feature_dim: None,
+            features: HashMap::new(),
+            labels: HashMap::new(),
         }
     }
 
+    pub fn set_multi_edge_policy(&mut self, policy: MultiEdgePolicy<E>) {
+        self.multi_edge_policy = policy;
+    }
+
+    pub fn set_self_loop_policy(&mut self, policy: SelfLoopPolicy) {
+        self.self_loop_policy = policy;
+    }
+
     pub fn add_node(&mut self, node: N) -> NodeIndex<u32> {
         match self.nodes.get(&node) {
             Some(nx) => *nx,
@@ -40,14 +151,65 @@ impl<N: NetworkNode, E: Copy> Network<N, E> {
         }
     }
 
-    pub fn add_edge(&mut self, from: N, to: N, edge_data: E) -> EdgeIndex<u32> {
+    /// Adds an edge according to the network's multi-edge and self-loop
+    /// policies. Returns the index of the inserted (or merged) edge, or
+    /// `None` if the edge was dropped.
+    pub fn add_edge(
+        &mut self,
+        from: N,
+        to: N,
+        edge_data: E,
+    ) -> Result<Option<EdgeIndex<u32>>, NetworkError> {
+        if from == to {
+            match self.self_loop_policy {
+                SelfLoopPolicy::Keep => {}
+                SelfLoopPolicy::Drop => {
+                    self.add_node(from);
+                    return Ok(None);
+                }
+                SelfLoopPolicy::Error => {
+                    return Err(NetworkError::SelfLoop(format!(
+                        "Self-loop ({:?}, {:?}) is not allowed",
+                        from, to
+                    )));
+                }
+            }
+        }
+
+        if let Some(existing) = self.edges.get(&(from, to)) {
+            let ex = existing[0];
+
+            match self.multi_edge_policy {
+                MultiEdgePolicy::KeepAll => {}
+                MultiEdgePolicy::Merge(merge) => {
+                    let merged = merge(self.graph[ex], edge_data);
+                    self.graph[ex] = merged;
+
+                    if !self.is_directed && from != to {
+                        let reverse_ex = self.edges[&(to, from)][0];
+                        self.graph[reverse_ex] = merged;
+                    }
+
+                    return Ok(Some(ex));
+                }
+                MultiEdgePolicy::Reject => {
+                    return Err(NetworkError::DuplicateEdge(format!(
+                        "Edge ({:?}, {:?}) already exists",
+                        from, to
+                    )));
+                }
+            }
+        }
+
         let ex = self._add_edge(from, to, edge_data);
 
-        if !self.is_directed {
+        if from == to {
+            self.num_self_loops += 1;
+        } else if !self.is_directed {
             self._add_edge(to, from, edge_data);
         }
 
-        ex
+        Ok(Some(ex))
     }
 
     fn _add_edge(&mut self, from: N, to: N, edge_data: E) -> EdgeIndex<u32> {
@@ -76,6 +238,16 @@ impl<N: NetworkNode, E: Copy> Network<N, E> {
         ex
     }
 
+    /// Adds a single stored edge as is, bypassing the policies. Both
+    /// directions of an undirected edge have to be restored separately.
+    pub(crate) fn restore_edge(&mut self, from: N, to: N, edge_data: E) {
+        self._add_edge(from, to, edge_data);
+
+        if from == to {
+            self.num_self_loops += 1;
+        }
+    }
+
     pub fn edge_data(&self, from: N, to: N) -> Option<Vec<&E>> {
         match self.edges.get(&(from, to)) {
             Some(ex) => ex.iter().map(|e| self.graph.edge_weight(*e)).collect(),
@@ -90,7 +262,8 @@ impl<N: NetworkNode, E: Copy> Network<N, E> {
     pub fn num_edges(&self) -> usize {
         let mut num_edges = self.graph.edge_count();
         if !self.is_directed {
-            num_edges /= 2;
+            // Self-loops are the only undirected edges stored once
+            num_edges = (num_edges - self.num_self_loops) / 2 + self.num_self_loops;
         }
 
         num_edges
@@ -104,6 +277,68 @@ impl<N: NetworkNode, E: Copy> Network<N, E> {
         self.edges.keys().collect()
     }
 
+    pub fn is_directed(&self) -> bool {
+        self.is_directed
+    }
+
+    pub(crate) fn graph(&self) -> &Graph<N, E> {
+        &self.graph
+    }
+
+    pub(crate) fn node_index(&self, node: N) -> Option<NodeIndex<u32>> {
+        self.nodes.get(&node).cloned()
+    }
+
+    /// Attaches features to a node (adding the node if needed). The first
+    /// features set fix the feature dimension for the whole network.
+    pub fn set_node_features(
+        &mut self,
+        node: N,
+        features: NodeFeatures,
+    ) -> Result<(), NetworkError> {
+        let dim = self.feature_dim.unwrap_or_else(|| features.dim());
+
+        if features.dim() != dim {
+            return Err(NetworkError::WrongFeatureDimension(format!(
+                "Expected: {} Got: {}",
+                dim,
+                features.dim()
+            )));
+        }
+
+        if let NodeFeatures::Sparse { entries, .. } = &features {
+            if let Some((idx, _)) = entries.iter().find(|(idx, _)| *idx >= dim) {
+                return Err(NetworkError::WrongFeatureDimension(format!(
+                    "Sparse index {} out of range for dimension {}",
+                    idx, dim
+                )));
+            }
+        }
+
+        self.feature_dim = Some(dim);
+        self.add_node(node);
+        self.features.insert(node, features);
+
+        Ok(())
+    }
+
+    pub fn node_features(&self, node: N) -> Option<&NodeFeatures> {
+        self.features.get(&node)
+    }
+
+    pub fn feature_dim(&self) -> Option<usize> {
+        self.feature_dim
+    }
+
+    pub fn set_node_label(&mut self, node: N, label: &str) {
+        self.add_node(node);
+        self.labels.insert(node, label.to_string());
+    }
+
+    pub fn node_label(&self, node: N) -> Option<&str> {
+        self.labels.get(&node).map(|l| l.as_str())
+    }
+
     pub fn neighbours_of(&self, node: N) -> Option<Vec<&N>> {
         match self.nodes.get(&node) {
             None => None,
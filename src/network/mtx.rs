@@ -0,0 +1,275 @@
+/// Matrix Market network reader and writer
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use petgraph::visit::EdgeRef;
+
+use crate::error::parse_token;
+use crate::network::model::*;
+use crate::network::reader::{NetworkReader, ReaderError, DEFAULT_MAX_NODES};
+use crate::network::writer::NetworkWriter;
+
+const BANNER: &str = "%%MatrixMarket";
+
+/// Reads Matrix Market coordinate files with `pattern`, `real` or `integer`
+/// entries. `general` matrices become directed networks and `symmetric` ones
+/// undirected networks. The 1-based row and column indices become 0-based
+/// node ids and every index up to the matrix size becomes a node. Pattern
+/// entries get a weight of 1.0.
+pub struct MatrixMarketReader {
+    max_nodes: usize,
+}
+
+impl Default for MatrixMarketReader {
+    fn default() -> Self {
+        MatrixMarketReader::new()
+    }
+}
+
+impl MatrixMarketReader {
+    pub fn new() -> MatrixMarketReader {
+        MatrixMarketReader {
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+
+    /// Largest matrix size accepted (2^24 by default), as every index up to
+    /// it becomes a node before any entry is read
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    fn read_lines<R: BufRead>(&self, mut reader: R) -> Result<Network<usize, f64>, ReaderError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+        let mut lines = content.lines().enumerate();
+
+        let banner = match lines.next() {
+            Some((_, banner)) => banner,
+            None => return Err(ReaderError::malformed("Missing header").at_line(1)),
+        };
+        let (pattern, directed) = parse_banner(banner).map_err(|e| e.at_line(1))?;
+
+        let mut lines = lines.filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('%'));
+
+        let (size, num_entries) = match lines.next() {
+            Some((idx, size_raw)) => {
+                let (size, num_entries) = parse_size(size_raw).map_err(|e| e.at_line(idx + 1))?;
+                if size > self.max_nodes {
+                    return Err(ReaderError::malformed(&format!(
+                        "Matrix size exceeds the limit of {} nodes",
+                        self.max_nodes
+                    ))
+                    .with_token(size_raw)
+                    .at_line(idx + 1));
+                }
+                (size, num_entries)
+            }
+            None => return Err(ReaderError::malformed("Missing matrix size")),
+        };
+
+        let mut net = Network::new(directed);
+        for node in 0..size {
+            net.add_node(node);
+        }
+
+        let mut read_entries = 0;
+        for (idx, entry_raw) in lines {
+            self.read_entry(entry_raw, pattern, size, &mut net)
+                .map_err(|e| e.at_line(idx + 1))?;
+            read_entries += 1;
+        }
+
+        if read_entries != num_entries {
+            return Err(ReaderError::malformed(&format!(
+                "Expected {} entries, got {}",
+                num_entries, read_entries
+            )));
+        }
+
+        Ok(net)
+    }
+
+    fn read_entry(
+        &self,
+        entry_raw: &str,
+        pattern: bool,
+        size: usize,
+        net: &mut Network<usize, f64>,
+    ) -> Result<(), ReaderError> {
+        let entry: Vec<&str> = entry_raw.split_whitespace().collect();
+
+        if entry.len() < if pattern { 2 } else { 3 } {
+            return Err(ReaderError::malformed("Not enough elements").with_token(entry_raw));
+        }
+
+        let index = |token: &str| -> Result<usize, ReaderError> {
+            let index: usize = parse_token(token, "int")?;
+            if index == 0 || index > size {
+                return Err(ReaderError::malformed("Index out of range").with_token(token));
+            }
+            Ok(index - 1)
+        };
+
+        let row = index(entry[0])?;
+        let col = index(entry[1])?;
+        let value: f64 = if pattern {
+            1.0
+        } else {
+            parse_token(entry[2], "float")?
+        };
+
+        net.add_edge(row, col, value)?;
+
+        Ok(())
+    }
+}
+
+/// Returns whether the entries are a pattern and whether the matrix is
+/// general (i.e. the network is directed)
+fn parse_banner(banner: &str) -> Result<(bool, bool), ReaderError> {
+    let fields: Vec<String> = banner
+        .split_whitespace()
+        .map(|f| f.to_lowercase())
+        .collect();
+
+    if fields.len() != 5 || fields[0] != BANNER.to_lowercase() || fields[1] != "matrix" {
+        return Err(ReaderError::malformed(
+            "Header should be: %%MatrixMarket matrix <format> <field> <symmetry>",
+        )
+        .with_token(banner));
+    }
+
+    if fields[2] != "coordinate" {
+        return Err(
+            ReaderError::malformed("Only coordinate matrices are supported").with_token(&fields[2]),
+        );
+    }
+
+    let pattern = match fields[3].as_str() {
+        "pattern" => true,
+        "real" | "integer" => false,
+        field => {
+            return Err(ReaderError::malformed("Unsupported matrix field").with_token(field));
+        }
+    };
+
+    let directed = match fields[4].as_str() {
+        "general" => true,
+        "symmetric" => false,
+        symmetry => {
+            return Err(ReaderError::malformed("Unsupported matrix symmetry").with_token(symmetry));
+        }
+    };
+
+    Ok((pattern, directed))
+}
+
+/// Returns the number of nodes (the larger matrix dimension) and entries
+fn parse_size(size_raw: &str) -> Result<(usize, usize), ReaderError> {
+    let size: Vec<&str> = size_raw.split_whitespace().collect();
+
+    if size.len() != 3 {
+        return Err(ReaderError::malformed(
+            "Size should contain 3 elements: rows, columns and entries",
+        )
+        .with_token(size_raw));
+    }
+
+    let rows: usize = parse_token(size[0], "int")?;
+    let cols: usize = parse_token(size[1], "int")?;
+    let num_entries: usize = parse_token(size[2], "int")?;
+
+    Ok((rows.max(cols), num_entries))
+}
+
+impl NetworkReader for MatrixMarketReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        self.read_lines(reader)
+    }
+}
+
+/// Writes a Matrix Market coordinate file, `general` for directed and
+/// `symmetric` (lower triangle) for undirected networks. Node ids are
+/// written as 1-based indices, the matrix size is the largest id plus one.
+pub struct MatrixMarketWriter {
+    write_weights: bool,
+}
+
+impl Default for MatrixMarketWriter {
+    fn default() -> Self {
+        MatrixMarketWriter::new()
+    }
+}
+
+impl MatrixMarketWriter {
+    pub fn new() -> MatrixMarketWriter {
+        MatrixMarketWriter {
+            write_weights: true,
+        }
+    }
+
+    /// Without weights a `pattern` matrix is written
+    pub fn set_write_weights(&mut self, write_weights: bool) {
+        self.write_weights = write_weights;
+    }
+}
+
+impl<E: Copy + Display> NetworkWriter<usize, E> for MatrixMarketWriter {
+    fn write(&self, net: &Network<usize, E>, file: PathBuf) -> Result<(), io::Error> {
+        let graph = net.graph();
+
+        let mut entries: Vec<(usize, usize, E)> = Vec::new();
+        for e in graph.edge_references() {
+            let (from, to) = (graph[e.source()], graph[e.target()]);
+
+            if net.is_directed() {
+                entries.push((from, to, *e.weight()));
+            } else if e.source().index() <= e.target().index() {
+                entries.push((from.max(to), from.min(to), *e.weight()));
+            }
+        }
+
+        let size = net.nodes().into_iter().max().map_or(0, |max| max + 1);
+
+        let field = if self.write_weights {
+            "real"
+        } else {
+            "pattern"
+        };
+        let symmetry = if net.is_directed() {
+            "general"
+        } else {
+            "symmetric"
+        };
+
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+
+        writeln!(
+            writer,
+            "{} matrix coordinate {} {}",
+            BANNER, field, symmetry
+        )?;
+        writeln!(writer, "{} {} {}", size, size, entries.len())?;
+
+        for (row, col, value) in entries {
+            if self.write_weights {
+                writeln!(writer, "{} {} {}", row + 1, col + 1, value)?;
+            } else {
+                writeln!(writer, "{} {}", row + 1, col + 1)?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/mtx_tests.rs"]
+mod mtx_tests;
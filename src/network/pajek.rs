@@ -0,0 +1,204 @@
+/// Pajek network reader
+use std::io::BufRead;
+
+use crate::error::parse_token;
+use crate::network::model::*;
+use crate::network::reader::{NetworkReader, ReaderError, DEFAULT_MAX_NODES};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Vertices,
+    Arcs,
+    Edges,
+    ArcsList,
+    EdgesList,
+}
+
+/// Splits a line on whitespace keeping double-quoted labels together
+fn split_tokens(line: &str) -> Result<Vec<&str>, ReaderError> {
+    let mut tokens = Vec::new();
+    let mut rest = line.trim_start();
+
+    while !rest.is_empty() {
+        let end = if let Some(quoted) = rest.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => {
+                    tokens.push(&quoted[..end]);
+                    end + 2
+                }
+                None => return Err(ReaderError::malformed("Unterminated label").with_token(rest)),
+            }
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            tokens.push(&rest[..end]);
+            end
+        };
+
+        rest = rest[end..].trim_start();
+    }
+
+    Ok(tokens)
+}
+
+/// Reads a Pajek `.net` file. Vertices keep their (1-based) numbers as node
+/// ids and their labels as network labels. The network is directed if it
+/// has any `*Arcs`; `*Edges` of a directed network are added in both
+/// directions. The weight is the third column of an arc or edge by
+/// default, 1.0 if absent.
+pub struct PajekReader {
+    weight_column: Option<usize>,
+    max_nodes: usize,
+}
+
+impl Default for PajekReader {
+    fn default() -> Self {
+        PajekReader::new()
+    }
+}
+
+impl PajekReader {
+    pub fn new() -> PajekReader {
+        PajekReader {
+            weight_column: Some(2),
+            max_nodes: DEFAULT_MAX_NODES,
+        }
+    }
+
+    /// Largest number of vertices a `*Vertices` line may declare (2^24 by
+    /// default), as they all become nodes before any vertex is listed
+    pub fn set_max_nodes(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// Selects the (0-based) column of `*Arcs` and `*Edges` lines holding
+    /// the weight. Without a weight column every edge gets a weight of 1.0.
+    pub fn set_weight_column(&mut self, column: Option<usize>) {
+        self.weight_column = column;
+    }
+
+    fn read_lines<R: BufRead>(&self, mut reader: R) -> Result<Network<usize, f64>, ReaderError> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content)?;
+
+        let directed = content
+            .lines()
+            .any(|l| l.trim_start().to_lowercase().starts_with("*arcs"));
+        let mut net = Network::new(directed);
+        let mut section = Section::None;
+
+        for (idx, line) in content.lines().enumerate() {
+            self.read_line(line, directed, &mut section, &mut net)
+                .map_err(|e| e.at_line(idx + 1))?;
+        }
+
+        Ok(net)
+    }
+
+    fn read_line(
+        &self,
+        line: &str,
+        directed: bool,
+        section: &mut Section,
+        net: &mut Network<usize, f64>,
+    ) -> Result<(), ReaderError> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('%') {
+            return Ok(());
+        }
+
+        let tokens = split_tokens(line)?;
+
+        if tokens[0].starts_with('*') {
+            *section = match tokens[0].to_lowercase().as_str() {
+                "*vertices" => {
+                    let num_vertices: usize = match tokens.get(1) {
+                        Some(n) => parse_token(n, "int")?,
+                        None => {
+                            return Err(ReaderError::malformed("Missing number of vertices")
+                                .with_token(line))
+                        }
+                    };
+                    if num_vertices > self.max_nodes {
+                        return Err(ReaderError::malformed(&format!(
+                            "Number of vertices exceeds the limit of {} nodes",
+                            self.max_nodes
+                        ))
+                        .with_token(tokens[1]));
+                    }
+                    for node in 1..=num_vertices {
+                        net.add_node(node);
+                    }
+                    Section::Vertices
+                }
+                "*arcs" => Section::Arcs,
+                "*edges" => Section::Edges,
+                "*arcslist" => Section::ArcsList,
+                "*edgeslist" => Section::EdgesList,
+                "*network" => Section::None,
+                _ => {
+                    return Err(ReaderError::malformed("Unknown section").with_token(tokens[0]));
+                }
+            };
+
+            return Ok(());
+        }
+
+        let add_edge = |net: &mut Network<usize, f64>, from, to, weight| {
+            net.add_edge(from, to, weight)?;
+            if *section != Section::Arcs && *section != Section::ArcsList && directed && from != to
+            {
+                net.add_edge(to, from, weight)?;
+            }
+            Ok::<(), ReaderError>(())
+        };
+
+        match section {
+            Section::None => {
+                return Err(ReaderError::malformed("Line outside of any section").with_token(line))
+            }
+            Section::Vertices => {
+                let node: usize = parse_token(tokens[0], "int")?;
+                net.add_node(node);
+                if let Some(label) = tokens.get(1) {
+                    net.set_node_label(node, label);
+                }
+            }
+            Section::Arcs | Section::Edges => {
+                if tokens.len() < 2 {
+                    return Err(ReaderError::malformed("Not enough elements").with_token(line));
+                }
+
+                let from: usize = parse_token(tokens[0], "int")?;
+                let to: usize = parse_token(tokens[1], "int")?;
+                let weight: f64 = match self.weight_column.and_then(|c| tokens.get(c)) {
+                    Some(w) => parse_token(w, "float")?,
+                    None => 1.0,
+                };
+
+                add_edge(net, from, to, weight)?;
+            }
+            Section::ArcsList | Section::EdgesList => {
+                let from: usize = parse_token(tokens[0], "int")?;
+                for to in tokens[1..].iter() {
+                    add_edge(net, from, parse_token(to, "int")?, 1.0)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl NetworkReader for PajekReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        self.read_lines(reader)
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/pajek_tests.rs"]
+mod pajek_tests;
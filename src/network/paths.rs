@@ -0,0 +1,286 @@
+/// Shortest paths
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use ndarray::prelude::*;
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+
+use crate::network::model::{Network, NetworkNode};
+
+#[derive(Debug, PartialEq)]
+pub enum PathsError {
+    NodeNotFound(String),
+    InvalidDistance(String),
+}
+
+/// Distances and shortest path tree from a single source node
+#[derive(Debug)]
+pub struct ShortestPaths<N: NetworkNode> {
+    source: N,
+    distances: HashMap<N, f64>,
+    predecessors: HashMap<N, N>,
+}
+
+impl<N: NetworkNode> ShortestPaths<N> {
+    pub fn source(&self) -> N {
+        self.source
+    }
+
+    pub fn distance(&self, to: N) -> Option<f64> {
+        self.distances.get(&to).cloned()
+    }
+
+    pub fn distances(&self) -> &HashMap<N, f64> {
+        &self.distances
+    }
+
+    /// Nodes on the shortest path from the source to `to` (both included)
+    pub fn path_to(&self, to: N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(&to) {
+            return None;
+        }
+
+        let mut path = vec![to];
+        let mut current = to;
+        while let Some(prev) = self.predecessors.get(&current) {
+            path.push(*prev);
+            current = *prev;
+        }
+        path.reverse();
+
+        Some(path)
+    }
+}
+
+/// Hop distances from `source`, ignoring edge data
+pub fn bfs<N: NetworkNode, E: Copy>(
+    net: &Network<N, E>,
+    source: N,
+) -> Result<ShortestPaths<N>, PathsError> {
+    let graph = net.graph();
+    let source_nx = net
+        .node_index(source)
+        .ok_or_else(|| PathsError::NodeNotFound("Source node is not in the network".to_string()))?;
+
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+
+    distances.insert(source, 0.0);
+
+    let mut queue = VecDeque::new();
+    queue.push_back(source_nx);
+
+    while let Some(v) = queue.pop_front() {
+        let dv = distances[&graph[v]];
+        for w in graph.neighbors(v) {
+            if let Entry::Vacant(entry) = distances.entry(graph[w]) {
+                entry.insert(dv + 1.0);
+                predecessors.insert(graph[w], graph[v]);
+                queue.push_back(w);
+            }
+        }
+    }
+
+    Ok(ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    })
+}
+
+#[derive(PartialEq)]
+struct HeapEntry {
+    dist: f64,
+    node: NodeIndex<u32>,
+}
+
+impl Eq for HeapEntry {}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, so that `BinaryHeap` pops the closest node first
+        other
+            .dist
+            .partial_cmp(&self.dist)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Weighted distances from `source`, using edge data as edge lengths.
+/// Parallel edges contribute their shortest length.
+pub fn dijkstra<N: NetworkNode>(
+    net: &Network<N, f64>,
+    source: N,
+) -> Result<ShortestPaths<N>, PathsError> {
+    check_distances(net)?;
+
+    let graph = net.graph();
+    let source_nx = net
+        .node_index(source)
+        .ok_or_else(|| PathsError::NodeNotFound("Source node is not in the network".to_string()))?;
+
+    let mut distances: HashMap<N, f64> = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut settled = vec![false; graph.node_count()];
+
+    distances.insert(source, 0.0);
+
+    let mut heap = BinaryHeap::new();
+    heap.push(HeapEntry {
+        dist: 0.0,
+        node: source_nx,
+    });
+
+    while let Some(HeapEntry { dist, node }) = heap.pop() {
+        if settled[node.index()] {
+            continue;
+        }
+        settled[node.index()] = true;
+
+        for e in graph.edges(node) {
+            let next = e.target();
+            let next_dist = dist + e.weight();
+
+            let is_shorter = match distances.get(&graph[next]) {
+                None => true,
+                Some(d) => next_dist < *d,
+            };
+
+            if is_shorter {
+                distances.insert(graph[next], next_dist);
+                predecessors.insert(graph[next], graph[node]);
+                heap.push(HeapEntry {
+                    dist: next_dist,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    Ok(ShortestPaths {
+        source,
+        distances,
+        predecessors,
+    })
+}
+
+/// Distances between every pair of nodes (Floyd-Warshall). Uses `O(n^2)`
+/// memory and `O(n^3)` time, so it is only meant for small networks.
+#[derive(Debug)]
+pub struct AllPairsShortestPaths<N: NetworkNode> {
+    index: HashMap<N, usize>,
+    nodes: Vec<N>,
+    distances: Array2<f64>,
+    next_hop: Array2<Option<usize>>,
+}
+
+impl<N: NetworkNode> AllPairsShortestPaths<N> {
+    pub fn distance(&self, from: N, to: N) -> Option<f64> {
+        let (i, j) = (*self.index.get(&from)?, *self.index.get(&to)?);
+        let d = self.distances[[i, j]];
+
+        if d.is_finite() {
+            Some(d)
+        } else {
+            None
+        }
+    }
+
+    pub fn path(&self, from: N, to: N) -> Option<Vec<N>> {
+        self.distance(from, to)?;
+
+        let (mut i, j) = (self.index[&from], self.index[&to]);
+        let mut path = vec![from];
+
+        while i != j {
+            i = self.next_hop[[i, j]]?;
+            path.push(self.nodes[i]);
+        }
+
+        Some(path)
+    }
+}
+
+/// Computes all pairs shortest paths. With `weighted` set, edge data is
+/// used as edge lengths, otherwise every edge has length 1.
+pub fn all_pairs<N: NetworkNode>(
+    net: &Network<N, f64>,
+    weighted: bool,
+) -> Result<AllPairsShortestPaths<N>, PathsError> {
+    if weighted {
+        check_distances(net)?;
+    }
+
+    let graph = net.graph();
+    let n = graph.node_count();
+
+    let mut distances = Array2::from_elem((n, n), f64::INFINITY);
+    let mut next_hop: Array2<Option<usize>> = Array2::from_elem((n, n), None);
+
+    for i in 0..n {
+        distances[[i, i]] = 0.0;
+        next_hop[[i, i]] = Some(i);
+    }
+
+    for e in graph.edge_references() {
+        let (i, j) = (e.source().index(), e.target().index());
+        let length = if weighted { *e.weight() } else { 1.0 };
+
+        if length < distances[[i, j]] {
+            distances[[i, j]] = length;
+            next_hop[[i, j]] = Some(j);
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            if !distances[[i, k]].is_finite() {
+                continue;
+            }
+            for j in 0..n {
+                let through_k = distances[[i, k]] + distances[[k, j]];
+                if through_k < distances[[i, j]] {
+                    distances[[i, j]] = through_k;
+                    next_hop[[i, j]] = next_hop[[i, k]];
+                }
+            }
+        }
+    }
+
+    Ok(AllPairsShortestPaths {
+        index: graph
+            .node_indices()
+            .map(|nx| (graph[nx], nx.index()))
+            .collect(),
+        nodes: graph.node_indices().map(|nx| graph[nx]).collect(),
+        distances,
+        next_hop,
+    })
+}
+
+fn check_distances<N: NetworkNode>(net: &Network<N, f64>) -> Result<(), PathsError> {
+    match net
+        .graph()
+        .edge_references()
+        .find(|e| e.weight().is_nan() || *e.weight() < 0.0)
+    {
+        Some(e) => Err(PathsError::InvalidDistance(format!(
+            "Edge lengths should be non-negative. Got: {}",
+            e.weight()
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/paths_tests.rs"]
+mod paths_tests;
@@ -2,90 +2,566 @@
 use std::fs;
 use std::io;
 use std::io::BufRead;
-use std::num;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use memmap2::Mmap;
+use ndarray::prelude::*;
+use rayon::prelude::*;
+
+use crate::compression;
+use crate::compression::Compression;
+use crate::error::parse_token;
+pub use crate::error::{ErrorKind, ReadReport, ReaderError};
 use crate::network::model::*;
+use crate::network::vocabulary::Vocabulary;
 
-#[derive(Debug, PartialEq)]
-pub enum ReaderError {
-    FileNotFound,
-    MalformedFileFormat(String),
-}
+pub trait NetworkReader {
+    type N: NetworkNode;
+    type E: Copy;
 
-impl From<io::Error> for ReaderError {
-    fn from(e: io::Error) -> Self {
-        match e.kind() {
-            io::ErrorKind::NotFound => ReaderError::FileNotFound,
-            _ => panic!(format!("Unknown error occurred: {:?}", e.kind())),
-        }
+    /// Reads a network from any buffered source, e.g. standard input or an
+    /// in-memory buffer. The input is read as is, without decompression.
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError>;
+
+    /// Reads a network from `file`, decompressing it if needed. Errors carry
+    /// the file path.
+    fn read(&self, file: PathBuf) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        compression::read_file(&file, |reader| self.read_from(reader))
     }
 }
 
-impl From<num::ParseIntError> for ReaderError {
-    fn from(_: num::ParseIntError) -> Self {
-        ReaderError::MalformedFileFormat("Couldn't parse int".to_string())
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Separator {
+    Char(char),
+    /// Any run of whitespace
+    Whitespace,
 }
 
-impl From<num::ParseFloatError> for ReaderError {
-    fn from(_: num::ParseFloatError) -> Self {
-        ReaderError::MalformedFileFormat("Couldn't parse float".to_string())
+impl Separator {
+    fn split<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self {
+            Separator::Char(c) => line.split(*c).collect(),
+            Separator::Whitespace => line.split_whitespace().collect(),
+        }
+    }
+
+    /// Separator used when writing, whitespace is written as a single space
+    pub(crate) fn as_str(&self) -> String {
+        match self {
+            Separator::Char(c) => c.to_string(),
+            Separator::Whitespace => " ".to_string(),
+        }
     }
 }
 
-pub trait NetworkReader {
-    type N: NetworkNode;
-    type E: Copy;
+const COMMENT_PREFIXES: [char; 2] = ['#', '%'];
 
-    fn read(&self, file: PathBuf) -> Result<Network<Self::N, Self::E>, ReaderError>;
-}
+/// Default limit on the number of nodes a file header may declare up front
+pub(crate) const DEFAULT_MAX_NODES: usize = 1 << 24;
 
 pub struct EdgeListReader {
-    separator: char,
+    separator: Separator,
     directed: bool,
+    multi_edge_policy: MultiEdgePolicy<f64>,
+    self_loop_policy: SelfLoopPolicy,
+    source_column: usize,
+    target_column: usize,
+    weight_column: Option<usize>,
+    skip_header: bool,
 }
 
 impl EdgeListReader {
     pub fn new(separator: char, directed: bool) -> EdgeListReader {
         EdgeListReader {
-            separator,
+            separator: Separator::Char(separator),
             directed,
+            multi_edge_policy: MultiEdgePolicy::KeepAll,
+            self_loop_policy: SelfLoopPolicy::Keep,
+            source_column: 0,
+            target_column: 1,
+            weight_column: Some(2),
+            skip_header: false,
+        }
+    }
+
+    pub fn set_separator(&mut self, separator: Separator) {
+        self.separator = separator;
+    }
+
+    /// Selects the columns holding the edge endpoints and weight. Without a
+    /// weight column every edge gets a weight of 1.0.
+    pub fn set_columns(&mut self, source: usize, target: usize, weight: Option<usize>) {
+        self.source_column = source;
+        self.target_column = target;
+        self.weight_column = weight;
+    }
+
+    pub fn set_skip_header(&mut self, skip_header: bool) {
+        self.skip_header = skip_header;
+    }
+
+    pub fn set_multi_edge_policy(&mut self, policy: MultiEdgePolicy<f64>) {
+        self.multi_edge_policy = policy;
+    }
+
+    pub fn set_self_loop_policy(&mut self, policy: SelfLoopPolicy) {
+        self.self_loop_policy = policy;
+    }
+}
+
+impl EdgeListReader {
+    /// Reads an edge list with arbitrary textual node ids. The ids are
+    /// interned into consecutive integers, the returned vocabulary maps
+    /// between both.
+    pub fn read_interned(
+        &self,
+        file: PathBuf,
+    ) -> Result<(Network<usize, f64>, Vocabulary), ReaderError> {
+        let mut vocab = Vocabulary::new();
+        let (net, _) = compression::read_file(&file, |reader| {
+            self.read_edges(reader, None, |node| Ok(vocab.intern(node)))
+        })?;
+
+        Ok((net, vocab))
+    }
+
+    /// Reads the edge list skipping up to `max_skipped` malformed lines.
+    /// Fails with the error of the first line over the limit.
+    pub fn read_lenient(
+        &self,
+        file: PathBuf,
+        max_skipped: usize,
+    ) -> Result<(Network<usize, f64>, ReadReport), ReaderError> {
+        let (net, report) = compression::read_file(&file, |reader| {
+            self.read_edges(reader, Some(max_skipped), |node| parse_token(node, "int"))
+        })?;
+
+        Ok((net, report.with_path(&file)))
+    }
+
+    fn read_edges<R, F>(
+        &self,
+        reader: R,
+        max_skipped: Option<usize>,
+        mut parse_node: F,
+    ) -> Result<(Network<usize, f64>, ReadReport), ReaderError>
+    where
+        R: BufRead,
+        F: FnMut(&str) -> Result<usize, ReaderError>,
+    {
+        let mut net = self.empty_network();
+
+        let report = for_each_line(reader, self.skip_header, max_skipped, |edge_raw| {
+            if let Some((from_node, to_node, edge_data)) =
+                self.parse_edge(edge_raw, &mut parse_node)?
+            {
+                net.add_edge(from_node, to_node, edge_data)?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok((net, report))
+    }
+
+    fn empty_network(&self) -> Network<usize, f64> {
+        let mut net = Network::new(self.directed);
+        net.set_multi_edge_policy(self.multi_edge_policy);
+        net.set_self_loop_policy(self.self_loop_policy);
+        net
+    }
+
+    /// Parses a single line into `(from, to, weight)`, `None` for blank and
+    /// comment lines. Only the needed columns are looked at, nothing is
+    /// allocated.
+    fn parse_edge<'a, F>(
+        &self,
+        edge_raw: &'a str,
+        parse_node: &mut F,
+    ) -> Result<Option<(usize, usize, f64)>, ReaderError>
+    where
+        F: FnMut(&str) -> Result<usize, ReaderError>,
+    {
+        if edge_raw.trim().is_empty() || edge_raw.starts_with(&COMMENT_PREFIXES[..]) {
+            return Ok(None);
+        }
+
+        let wanted = [
+            Some(self.source_column),
+            Some(self.target_column),
+            self.weight_column,
+        ];
+        let mut columns: [Option<&'a str>; 3] = [None; 3];
+        let mut visit = |idx: usize, field: &'a str| {
+            for (column, want) in columns.iter_mut().zip(wanted.iter()) {
+                if *want == Some(idx) {
+                    *column = Some(field);
+                }
+            }
+        };
+
+        match self.separator {
+            Separator::Char(c) => edge_raw
+                .split(c)
+                .enumerate()
+                .for_each(|(idx, field)| visit(idx, field)),
+            Separator::Whitespace => edge_raw
+                .split_whitespace()
+                .enumerate()
+                .for_each(|(idx, field)| visit(idx, field)),
+        }
+
+        let (from_raw, to_raw, weight_raw) = match columns {
+            [Some(from), Some(to), weight] if weight.is_some() == self.weight_column.is_some() => {
+                (from, to, weight)
+            }
+            _ => return Err(ReaderError::malformed("Not enough elements").with_token(edge_raw)),
+        };
+
+        let from_node = parse_node(from_raw)?;
+        let to_node = parse_node(to_raw)?;
+        let edge_data: f64 = match weight_raw {
+            Some(weight) => parse_token(weight, "float")?,
+            None => 1.0,
+        };
+
+        Ok(Some((from_node, to_node, edge_data)))
+    }
+
+    /// Reads the edge list of a memory-mapped `file`. The file is split at
+    /// line boundaries into chunks that are parsed on the rayon thread pool;
+    /// edges are then added in file order, so the network (and any error) is
+    /// the same as with `read`. Compressed files are read sequentially.
+    pub fn read_parallel(&self, file: PathBuf) -> Result<Network<usize, f64>, ReaderError> {
+        if Compression::detect(&file).map_err(|e| ReaderError::from(e).with_path(&file))?
+            != Compression::None
+        {
+            return self.read(file);
         }
+
+        self.read_mapped(&file).map_err(|e| e.with_path(&file))
+    }
+
+    fn read_mapped(&self, file: &Path) -> Result<Network<usize, f64>, ReaderError> {
+        let f = fs::File::open(file)?;
+        // Safety: the map is only read, modifying the file while reading it
+        // is the caller's responsibility (as with any other reader)
+        let data = unsafe { Mmap::map(&f)? };
+
+        let (data, first_line) = if self.skip_header {
+            match data.iter().position(|b| *b == b'\n') {
+                Some(pos) => (&data[pos + 1..], 2),
+                None => (&data[data.len()..], 2),
+            }
+        } else {
+            (&data[..], 1)
+        };
+
+        let chunks = split_lines(data, rayon::current_num_threads() * 4);
+
+        let parsed: Vec<ParsedChunk> = chunks
+            .par_iter()
+            .map(|chunk| {
+                let mut edges = Vec::new();
+                for (idx, line) in chunk.split(|b| *b == b'\n').enumerate() {
+                    let res = line_to_str(line)
+                        .and_then(|l| self.parse_edge(l, &mut |n| parse_token(n, "int")));
+
+                    match res {
+                        Ok(Some(edge)) => edges.push((idx, edge)),
+                        Ok(None) => {}
+                        Err(e) => return (edges, Some((idx, e))),
+                    }
+                }
+                (edges, None)
+            })
+            .collect();
+
+        let mut net = self.empty_network();
+        let mut chunk_line = first_line;
+
+        for (chunk, (edges, error)) in chunks.iter().zip(parsed) {
+            // Line numbers within a chunk are relative to its start
+            let at_line = |e: ReaderError, idx: usize| e.at_line(chunk_line + idx);
+
+            // Edges before a parse error go first, so that an earlier
+            // rejected edge is reported as it would be by `read`
+            for (idx, (from, to, weight)) in edges {
+                net.add_edge(from, to, weight)
+                    .map_err(|e| at_line(ReaderError::from(e), idx))?;
+            }
+            if let Some((idx, e)) = error {
+                return Err(at_line(e, idx));
+            }
+
+            chunk_line += chunk.iter().filter(|b| **b == b'\n').count();
+        }
+
+        Ok(net)
+    }
+}
+
+/// `(from, to, weight)` of a parsed edge list line
+type ParsedEdge = (usize, usize, f64);
+
+/// Edges of a chunk with their line index in it, and the parse error that
+/// stopped it, if any
+type ParsedChunk = (Vec<(usize, ParsedEdge)>, Option<(usize, ReaderError)>);
+
+/// Splits `data` into about `num_chunks` chunks, each ending at a newline
+/// (except possibly the last one)
+fn split_lines(data: &[u8], num_chunks: usize) -> Vec<&[u8]> {
+    let chunk_size = (data.len() / num_chunks.max(1)).max(1);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < data.len() {
+        let end = (start + chunk_size).min(data.len());
+        let end = match data[end - 1..].iter().position(|b| *b == b'\n') {
+            Some(pos) => end + pos,
+            None => data.len(),
+        };
+
+        chunks.push(&data[start..end]);
+        start = end;
     }
+
+    chunks
+}
+
+/// Same as the line handling of `BufRead::lines`: strips `\r` and fails on
+/// invalid UTF-8
+fn line_to_str(line: &[u8]) -> Result<&str, ReaderError> {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+    std::str::from_utf8(line).map_err(|_| {
+        ReaderError::from(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        ))
+    })
 }
 
 impl NetworkReader for EdgeListReader {
     type N = usize;
     type E = f64;
 
-    fn read(&self, file: PathBuf) -> Result<Network<Self::N, Self::E>, ReaderError> {
-        let f = fs::File::open(file)?;
-        let reader = io::BufReader::new(&f);
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
+        let (net, _) = self.read_edges(reader, None, |node| parse_token(node, "int"))?;
+
+        Ok(net)
+    }
+}
+
+/// Reads `node<sep>neighbour<sep>weight<sep>neighbour<sep>weight...` rows
+/// (`node<sep>neighbour<sep>neighbour...` when unweighted). A row holding
+/// only the node adds it without any edges.
+pub struct AdjacencyListReader {
+    separator: Separator,
+    directed: bool,
+    weighted: bool,
+}
+
+impl AdjacencyListReader {
+    pub fn new(separator: char, directed: bool) -> AdjacencyListReader {
+        AdjacencyListReader {
+            separator: Separator::Char(separator),
+            directed,
+            weighted: true,
+        }
+    }
+
+    pub fn set_separator(&mut self, separator: Separator) {
+        self.separator = separator;
+    }
 
+    /// Without weights every edge gets a weight of 1.0
+    pub fn set_weighted(&mut self, weighted: bool) {
+        self.weighted = weighted;
+    }
+}
+
+impl NetworkReader for AdjacencyListReader {
+    type N = usize;
+    type E = f64;
+
+    fn read_from<R: BufRead>(&self, reader: R) -> Result<Network<Self::N, Self::E>, ReaderError> {
         let mut net = Network::new(self.directed);
 
-        for line in reader.lines() {
-            let edge_raw: String = line?;
-            let edge: Vec<&str> = edge_raw.split(self.separator).collect();
+        for_each_line(reader, false, None, |row_raw| {
+            if row_raw.trim().is_empty() || row_raw.starts_with(&COMMENT_PREFIXES[..]) {
+                return Ok(());
+            }
 
-            if edge.len() < 3 {
-                return Err(ReaderError::MalformedFileFormat(
-                    "Not enough elements".to_string(),
-                ));
+            let row: Vec<&str> = self.separator.split(row_raw);
+
+            let node: usize = parse_token(row[0], "int")?;
+            net.add_node(node);
+
+            let step = if self.weighted { 2 } else { 1 };
+            if (row.len() - 1) % step != 0 {
+                return Err(
+                    ReaderError::malformed("Every neighbour should have a weight")
+                        .with_token(row_raw),
+                );
             }
 
-            let from_node: usize = edge[0].parse()?;
-            let to_node: usize = edge[1].parse()?;
-            let edge_data: f64 = edge[2].parse()?;
+            for entry in row[1..].chunks(step) {
+                let neighbour: usize = parse_token(entry[0], "int")?;
+                let edge_data: f64 = match entry.get(1) {
+                    Some(weight) => parse_token(weight, "float")?,
+                    None => 1.0,
+                };
 
-            net.add_edge(from_node, to_node, edge_data);
-        }
+                net.add_edge(node, neighbour, edge_data)?;
+            }
+
+            Ok(())
+        })?;
 
         Ok(net)
     }
 }
 
+/// Calls `f` on every line of `reader`, tagging errors with the (1-based)
+/// line number. Malformed lines are skipped up to `max_skipped`, if given.
+fn for_each_line<R, F>(
+    reader: R,
+    skip_header: bool,
+    max_skipped: Option<usize>,
+    mut f: F,
+) -> Result<ReadReport, ReaderError>
+where
+    R: BufRead,
+    F: FnMut(&str) -> Result<(), ReaderError>,
+{
+    let mut report = ReadReport::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        if skip_header && idx == 0 {
+            continue;
+        }
+
+        let res = line.map_err(ReaderError::from).and_then(|l| f(&l));
+
+        if let Err(e) = res {
+            report.skip(e.at_line(idx + 1), max_skipped)?;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Layout of the rows in a node features file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeaturesFormat {
+    /// `node,f_0,f_1,...,f_n`
+    Dense,
+    /// `node,idx:value,idx:value,...` with the given feature dimension
+    Sparse(usize),
+}
+
+/// Reads a companion node features file into an existing network
+pub struct NodeFeaturesReader {
+    separator: char,
+    format: FeaturesFormat,
+}
+
+impl NodeFeaturesReader {
+    pub fn new(separator: char, format: FeaturesFormat) -> NodeFeaturesReader {
+        NodeFeaturesReader { separator, format }
+    }
+
+    pub fn read_into(
+        &self,
+        file: PathBuf,
+        net: &mut Network<usize, f64>,
+    ) -> Result<(), ReaderError> {
+        compression::read_file(&file, |reader| {
+            for_each_line(reader, false, None, |row_raw| {
+                let row: Vec<&str> = row_raw.split(self.separator).collect();
+
+                // Sparse rows may list no entries at all (all-zero features)
+                if row.len() < 2 && self.format == FeaturesFormat::Dense {
+                    return Err(ReaderError::malformed("Not enough elements").with_token(row_raw));
+                }
+
+                let node: usize = parse_token(row[0], "int")?;
+
+                let features = match self.format {
+                    FeaturesFormat::Dense => {
+                        let mut vector: Vec<f64> = Vec::new();
+                        for v in row[1..].iter() {
+                            vector.push(parse_token(v, "float")?);
+                        }
+                        NodeFeatures::Dense(Array1::from_vec(vector))
+                    }
+                    FeaturesFormat::Sparse(dim) => {
+                        let mut entries: Vec<(usize, f64)> = Vec::new();
+                        for entry in row[1..].iter() {
+                            let pair: Vec<&str> = entry.split(':').collect();
+                            if pair.len() != 2 {
+                                return Err(ReaderError::malformed(
+                                    "Sparse feature should be idx:value",
+                                )
+                                .with_token(entry));
+                            }
+                            entries.push((
+                                parse_token(pair[0], "int")?,
+                                parse_token(pair[1], "float")?,
+                            ));
+                        }
+                        NodeFeatures::Sparse { dim, entries }
+                    }
+                };
+
+                net.set_node_features(node, features)?;
+
+                Ok(())
+            })
+        })?;
+
+        Ok(())
+    }
+}
+
+/// Reads a companion `node,label` file into an existing network
+pub struct NodeLabelsReader {
+    separator: char,
+}
+
+impl NodeLabelsReader {
+    pub fn new(separator: char) -> NodeLabelsReader {
+        NodeLabelsReader { separator }
+    }
+
+    pub fn read_into(
+        &self,
+        file: PathBuf,
+        net: &mut Network<usize, f64>,
+    ) -> Result<(), ReaderError> {
+        compression::read_file(&file, |reader| {
+            for_each_line(reader, false, None, |row_raw| {
+                let row: Vec<&str> = row_raw.split(self.separator).collect();
+
+                if row.len() != 2 {
+                    return Err(ReaderError::malformed(
+                        "Label row should contain 2 elements: node and label",
+                    )
+                    .with_token(row_raw));
+                }
+
+                let node: usize = parse_token(row[0], "int")?;
+                net.set_node_label(node, row[1]);
+
+                Ok(())
+            })
+        })?;
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 #[path = "../../tests/unit/network/reader_tests.rs"]
 mod reader_tests;
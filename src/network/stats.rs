@@ -0,0 +1,296 @@
+/// Network statistics
+use std::collections::{HashMap, HashSet};
+
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use crate::network::model::{Network, NetworkNode};
+
+#[derive(Debug, Default, PartialEq)]
+pub struct DegreePercentiles {
+    pub min: usize,
+    pub p25: usize,
+    pub median: usize,
+    pub p75: usize,
+    pub p90: usize,
+    pub p99: usize,
+    pub max: usize,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct NetworkStats {
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub density: f64,
+    pub num_self_loops: usize,
+    pub num_multi_edges: usize,
+    pub num_isolated_nodes: usize,
+    pub mean_degree: f64,
+    pub degree_percentiles: DegreePercentiles,
+    pub num_components: usize,
+    pub avg_clustering_coefficient: f64,
+    pub degree_assortativity: Option<f64>,
+}
+
+impl NetworkStats {
+    /// Computes a summary of the given network. Degrees count every edge
+    /// (including parallel ones and both endpoints of self-loops); components
+    /// are weakly connected ones and clustering is computed on the simple,
+    /// undirected view of the network.
+    pub fn compute<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> NetworkStats {
+        let graph = net.graph();
+        let n = graph.node_count();
+        let m = net.num_edges();
+
+        let degrees: Vec<usize> = graph
+            .node_indices()
+            .map(|nx| node_degree(net, nx))
+            .collect();
+
+        let (num_self_loops, num_multi_edges) = count_loops_and_multi_edges(net);
+
+        NetworkStats {
+            num_nodes: n,
+            num_edges: m,
+            density: density(n, m, net.is_directed()),
+            num_self_loops,
+            num_multi_edges,
+            num_isolated_nodes: degrees.iter().filter(|d| **d == 0).count(),
+            mean_degree: mean(&degrees),
+            degree_percentiles: degree_percentiles(&degrees),
+            num_components: num_components(net),
+            avg_clustering_coefficient: avg_clustering_coefficient(net),
+            degree_assortativity: degree_assortativity(net),
+        }
+    }
+}
+
+fn node_degree<N: NetworkNode, E: Copy>(net: &Network<N, E>, nx: NodeIndex<u32>) -> usize {
+    let graph = net.graph();
+    let out_degree = graph.edges_directed(nx, Direction::Outgoing).count();
+
+    if net.is_directed() {
+        out_degree + graph.edges_directed(nx, Direction::Incoming).count()
+    } else {
+        // Undirected edges are stored in both directions, so the outgoing
+        // ones cover every incident edge. Self-loops are stored once but
+        // count towards the degree twice.
+        let num_self_loops = graph
+            .edges_directed(nx, Direction::Outgoing)
+            .filter(|e| e.target() == nx)
+            .count();
+
+        out_degree + num_self_loops
+    }
+}
+
+fn density(num_nodes: usize, num_edges: usize, directed: bool) -> f64 {
+    if num_nodes < 2 {
+        return 0.0;
+    }
+
+    let max_edges = (num_nodes * (num_nodes - 1)) as f64;
+    if directed {
+        num_edges as f64 / max_edges
+    } else {
+        2.0 * num_edges as f64 / max_edges
+    }
+}
+
+fn count_loops_and_multi_edges<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> (usize, usize) {
+    let graph = net.graph();
+    let mut multiplicities: HashMap<(NodeIndex<u32>, NodeIndex<u32>), usize> = HashMap::new();
+
+    for e in graph.edge_references() {
+        *multiplicities.entry((e.source(), e.target())).or_insert(0) += 1;
+    }
+
+    let mut num_self_loops = 0;
+    let mut num_multi_edges = 0;
+
+    for ((from, to), count) in multiplicities {
+        if from == to {
+            num_self_loops += count;
+            num_multi_edges += count - 1;
+        } else if net.is_directed() || from < to {
+            num_multi_edges += count - 1;
+        }
+    }
+
+    (num_self_loops, num_multi_edges)
+}
+
+fn mean(values: &[usize]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().sum::<usize>() as f64 / values.len() as f64
+}
+
+fn degree_percentiles(degrees: &[usize]) -> DegreePercentiles {
+    if degrees.is_empty() {
+        return DegreePercentiles::default();
+    }
+
+    let mut sorted = degrees.to_vec();
+    sorted.sort();
+
+    // Nearest-rank method
+    let percentile = |q: f64| {
+        let rank = (q / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted[rank.max(1) - 1]
+    };
+
+    DegreePercentiles {
+        min: sorted[0],
+        p25: percentile(25.0),
+        median: percentile(50.0),
+        p75: percentile(75.0),
+        p90: percentile(90.0),
+        p99: percentile(99.0),
+        max: sorted[sorted.len() - 1],
+    }
+}
+
+fn num_components<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> usize {
+    let graph = net.graph();
+    let mut visited = vec![false; graph.node_count()];
+    let mut components = 0;
+
+    for start in graph.node_indices() {
+        if visited[start.index()] {
+            continue;
+        }
+
+        components += 1;
+        visited[start.index()] = true;
+
+        let mut stack = vec![start];
+        while let Some(nx) = stack.pop() {
+            for neighbour in graph.neighbors_undirected(nx) {
+                if !visited[neighbour.index()] {
+                    visited[neighbour.index()] = true;
+                    stack.push(neighbour);
+                }
+            }
+        }
+    }
+
+    components
+}
+
+fn simple_neighbourhoods<N: NetworkNode, E: Copy>(
+    net: &Network<N, E>,
+) -> Vec<HashSet<NodeIndex<u32>>> {
+    let graph = net.graph();
+
+    graph
+        .node_indices()
+        .map(|nx| {
+            graph
+                .neighbors_undirected(nx)
+                .filter(|neighbour| *neighbour != nx)
+                .collect()
+        })
+        .collect()
+}
+
+fn avg_clustering_coefficient<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> f64 {
+    let neighbourhoods = simple_neighbourhoods(net);
+
+    if neighbourhoods.is_empty() {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+
+    for neighbours in neighbourhoods.iter() {
+        let k = neighbours.len();
+        if k < 2 {
+            continue;
+        }
+
+        let mut links = 0;
+        for u in neighbours.iter() {
+            for v in neighbourhoods[u.index()].iter() {
+                if neighbours.contains(v) {
+                    links += 1;
+                }
+            }
+        }
+
+        // Every link between neighbours was counted from both of its ends
+        total += links as f64 / (k * (k - 1)) as f64;
+    }
+
+    total / neighbourhoods.len() as f64
+}
+
+fn degree_assortativity<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> Option<f64> {
+    let graph = net.graph();
+
+    // For directed networks the out-degree of the source is correlated with
+    // the in-degree of the target.
+    let (src_degree, dst_degree): (Vec<usize>, Vec<usize>) = if net.is_directed() {
+        (
+            graph
+                .node_indices()
+                .map(|nx| graph.edges_directed(nx, Direction::Outgoing).count())
+                .collect(),
+            graph
+                .node_indices()
+                .map(|nx| graph.edges_directed(nx, Direction::Incoming).count())
+                .collect(),
+        )
+    } else {
+        let degrees: Vec<usize> = graph
+            .node_indices()
+            .map(|nx| node_degree(net, nx))
+            .collect();
+        (degrees.clone(), degrees)
+    };
+
+    let pairs: Vec<(f64, f64)> = graph
+        .edge_references()
+        .map(|e| {
+            (
+                src_degree[e.source().index()] as f64,
+                dst_degree[e.target().index()] as f64,
+            )
+        })
+        .collect();
+
+    pearson_correlation(&pairs)
+}
+
+fn pearson_correlation(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.is_empty() {
+        return None;
+    }
+
+    let len = pairs.len() as f64;
+    let mean_x = pairs.iter().map(|p| p.0).sum::<f64>() / len;
+    let mean_y = pairs.iter().map(|p| p.1).sum::<f64>() / len;
+
+    let mut cov = 0.0;
+    let mut var_x = 0.0;
+    let mut var_y = 0.0;
+
+    for (x, y) in pairs.iter() {
+        cov += (x - mean_x) * (y - mean_y);
+        var_x += (x - mean_x).powi(2);
+        var_y += (y - mean_y).powi(2);
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+
+    Some(cov / (var_x.sqrt() * var_y.sqrt()))
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/stats_tests.rs"]
+mod stats_tests;
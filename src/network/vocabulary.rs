@@ -0,0 +1,70 @@
+/// Bidirectional mapping between textual node ids and compact integer ids
+use std::collections::HashMap;
+
+use crate::embedding::model::{Embedding, EmbeddingError};
+
+#[derive(Debug, Default)]
+pub struct Vocabulary {
+    ids: HashMap<String, usize>,
+    names: Vec<String>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Vocabulary {
+        Vocabulary {
+            ids: HashMap::new(),
+            names: Vec::new(),
+        }
+    }
+
+    /// Returns the id of `name`, assigning the next free one if it is new
+    pub fn intern(&mut self, name: &str) -> usize {
+        match self.ids.get(name) {
+            Some(id) => *id,
+            None => {
+                let id = self.names.len();
+                self.ids.insert(name.to_string(), id);
+                self.names.push(name.to_string());
+
+                id
+            }
+        }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn name(&self, id: usize) -> Option<&str> {
+        self.names.get(id).map(|n| n.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Re-keys an embedding learned on interned ids with the original names
+    pub fn decode_embedding(
+        &self,
+        emb: &Embedding<usize>,
+    ) -> Result<Embedding<&str>, EmbeddingError> {
+        let mut decoded = Embedding::new(emb.dim());
+
+        for id in emb.keys() {
+            let name = self.name(*id).ok_or_else(|| {
+                EmbeddingError::VectorNotFound(format!("No name found for {:?}", id))
+            })?;
+            decoded.add_vector(name, emb.get_vector(*id)?)?;
+        }
+
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/vocabulary_tests.rs"]
+mod vocabulary_tests;
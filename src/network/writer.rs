@@ -0,0 +1,131 @@
+/// Network file writer
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::PathBuf;
+
+use petgraph::visit::EdgeRef;
+
+use crate::network::model::*;
+use crate::network::reader::Separator;
+
+pub trait NetworkWriter<N: NetworkNode, E: Copy> {
+    fn write(&self, net: &Network<N, E>, file: PathBuf) -> Result<(), io::Error>;
+}
+
+/// Outgoing edges of every node (by node index) in insertion order.
+/// Undirected edges are listed once, under their endpoint with the lower
+/// index, so reading them back restores the network.
+fn outgoing_edges<N: NetworkNode, E: Copy>(net: &Network<N, E>) -> Vec<Vec<(N, E)>> {
+    let graph = net.graph();
+    let mut outgoing: Vec<Vec<(N, E)>> = vec![Vec::new(); graph.node_count()];
+
+    for e in graph.edge_references() {
+        if !net.is_directed() && e.source().index() > e.target().index() {
+            continue;
+        }
+
+        outgoing[e.source().index()].push((graph[e.target()], *e.weight()));
+    }
+
+    outgoing
+}
+
+/// Writes one `from<sep>to<sep>weight` row per edge. Isolated nodes can't
+/// be represented in this format and are not written.
+pub struct EdgeListWriter {
+    separator: Separator,
+    write_weights: bool,
+}
+
+impl EdgeListWriter {
+    pub fn new(separator: char) -> EdgeListWriter {
+        EdgeListWriter {
+            separator: Separator::Char(separator),
+            write_weights: true,
+        }
+    }
+
+    pub fn set_separator(&mut self, separator: Separator) {
+        self.separator = separator;
+    }
+
+    pub fn set_write_weights(&mut self, write_weights: bool) {
+        self.write_weights = write_weights;
+    }
+}
+
+impl<N: NetworkNode + Display, E: Copy + Display> NetworkWriter<N, E> for EdgeListWriter {
+    fn write(&self, net: &Network<N, E>, file: PathBuf) -> Result<(), io::Error> {
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+        let sep = self.separator.as_str();
+        let graph = net.graph();
+        let outgoing = outgoing_edges(net);
+
+        for nx in graph.node_indices() {
+            for (to, data) in outgoing[nx.index()].iter() {
+                if self.write_weights {
+                    writeln!(writer, "{}{}{}{}{}", graph[nx], sep, to, sep, data)?;
+                } else {
+                    writeln!(writer, "{}{}{}", graph[nx], sep, to)?;
+                }
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+/// Writes one `node<sep>neighbour<sep>weight<sep>neighbour<sep>weight...`
+/// row per node (`node<sep>neighbour<sep>neighbour...` without weights).
+/// Every node gets a row, so isolated nodes are preserved.
+pub struct AdjacencyListWriter {
+    separator: Separator,
+    write_weights: bool,
+}
+
+impl AdjacencyListWriter {
+    pub fn new(separator: char) -> AdjacencyListWriter {
+        AdjacencyListWriter {
+            separator: Separator::Char(separator),
+            write_weights: true,
+        }
+    }
+
+    pub fn set_separator(&mut self, separator: Separator) {
+        self.separator = separator;
+    }
+
+    pub fn set_write_weights(&mut self, write_weights: bool) {
+        self.write_weights = write_weights;
+    }
+}
+
+impl<N: NetworkNode + Display, E: Copy + Display> NetworkWriter<N, E> for AdjacencyListWriter {
+    fn write(&self, net: &Network<N, E>, file: PathBuf) -> Result<(), io::Error> {
+        let mut writer = io::BufWriter::new(fs::File::create(file)?);
+        let sep = self.separator.as_str();
+        let graph = net.graph();
+        let outgoing = outgoing_edges(net);
+
+        for nx in graph.node_indices() {
+            write!(writer, "{}", graph[nx])?;
+
+            for (to, data) in outgoing[nx.index()].iter() {
+                write!(writer, "{}{}", sep, to)?;
+                if self.write_weights {
+                    write!(writer, "{}{}", sep, data)?;
+                }
+            }
+
+            writeln!(writer)?;
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+#[path = "../../tests/unit/network/writer_tests.rs"]
+mod writer_tests;
@@ -0,0 +1,245 @@
+/// serde support for networks, embeddings and walkers
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::algorithm::random_walk::NonBiasedRandomWalker;
+use crate::embedding::model::*;
+use crate::network::model::*;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "NodeFeatures")]
+enum FeaturesData {
+    Dense(Vec<f64>),
+    Sparse {
+        dim: usize,
+        entries: Vec<(usize, f64)>,
+    },
+}
+
+impl From<&NodeFeatures> for FeaturesData {
+    fn from(features: &NodeFeatures) -> Self {
+        match features {
+            NodeFeatures::Dense(values) => FeaturesData::Dense(values.to_vec()),
+            NodeFeatures::Sparse { dim, entries } => FeaturesData::Sparse {
+                dim: *dim,
+                entries: entries.clone(),
+            },
+        }
+    }
+}
+
+impl From<FeaturesData> for NodeFeatures {
+    fn from(features: FeaturesData) -> Self {
+        match features {
+            FeaturesData::Dense(values) => NodeFeatures::Dense(values.into()),
+            FeaturesData::Sparse { dim, entries } => NodeFeatures::Sparse { dim, entries },
+        }
+    }
+}
+
+impl Serialize for NodeFeatures {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FeaturesData::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NodeFeatures {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        FeaturesData::deserialize(deserializer).map(NodeFeatures::from)
+    }
+}
+
+/// Nodes and edges in insertion order, undirected edges listed once, from
+/// the endpoint added first. Graph indices and edge policies are not
+/// stored.
+#[derive(Serialize)]
+#[serde(rename = "Network")]
+struct NetworkRef<'a, N, E> {
+    directed: bool,
+    nodes: Vec<&'a N>,
+    edges: Vec<(&'a N, &'a N, &'a E)>,
+    labels: Vec<(&'a N, &'a str)>,
+    features: Vec<(&'a N, &'a NodeFeatures)>,
+}
+
+#[derive(Deserialize)]
+#[serde(
+    rename = "Network",
+    bound(deserialize = "N: Deserialize<'de>, E: Deserialize<'de>")
+)]
+struct NetworkData<N, E> {
+    directed: bool,
+    nodes: Vec<N>,
+    edges: Vec<(N, N, E)>,
+    #[serde(default)]
+    labels: Vec<(N, String)>,
+    #[serde(default)]
+    features: Vec<(N, NodeFeatures)>,
+}
+
+impl<N, E> Serialize for Network<N, E>
+where
+    N: NetworkNode + Serialize,
+    E: Copy + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let graph = self.graph();
+        let nodes: Vec<&N> = graph.node_indices().map(|nx| &graph[nx]).collect();
+
+        // Both directions of an undirected edge are stored, only the one
+        // going from the lower node index is kept
+        let edges = graph
+            .edge_indices()
+            .filter_map(|ex| {
+                let (from, to) = graph.edge_endpoints(ex).unwrap();
+                if self.is_directed() || from <= to {
+                    Some((&graph[from], &graph[to], &graph[ex]))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        NetworkRef {
+            directed: self.is_directed(),
+            labels: nodes
+                .iter()
+                .filter_map(|n| self.node_label(**n).map(|l| (*n, l)))
+                .collect(),
+            features: nodes
+                .iter()
+                .filter_map(|n| self.node_features(**n).map(|f| (*n, f)))
+                .collect(),
+            nodes,
+            edges,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, N, E> Deserialize<'de> for Network<N, E>
+where
+    N: NetworkNode + Deserialize<'de>,
+    E: Copy + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = NetworkData::<N, E>::deserialize(deserializer)?;
+        let mut net = Network::new(data.directed);
+
+        for node in data.nodes.iter() {
+            net.add_node(*node);
+        }
+        if net.num_nodes() != data.nodes.len() {
+            return Err(D::Error::custom("duplicate node"));
+        }
+
+        for (from, to, edge_data) in data.edges {
+            check_node(&net, from)?;
+            check_node(&net, to)?;
+            net.restore_edge(from, to, edge_data);
+            if !data.directed && from != to {
+                net.restore_edge(to, from, edge_data);
+            }
+        }
+
+        for (node, label) in data.labels {
+            check_node(&net, node)?;
+            net.set_node_label(node, &label);
+        }
+
+        for (node, features) in data.features {
+            check_node(&net, node)?;
+            net.set_node_features(node, features)
+                .map_err(D::Error::custom)?;
+        }
+
+        Ok(net)
+    }
+}
+
+/// Fails for nodes missing from the `nodes` list of the input
+fn check_node<N: NetworkNode, E: Copy, Er: Error>(net: &Network<N, E>, node: N) -> Result<(), Er> {
+    match net.node_index(node) {
+        Some(_) => Ok(()),
+        None => Err(Er::custom(format!("undeclared node {:?}", node))),
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename = "Embedding")]
+struct EmbeddingRef<'a, K> {
+    dim: usize,
+    vectors: Vec<(&'a K, &'a [f64])>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Embedding")]
+struct EmbeddingData<K> {
+    dim: usize,
+    vectors: Vec<(K, Vec<f64>)>,
+}
+
+impl<K: EmbeddingKey + Serialize> Serialize for Embedding<K> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        EmbeddingRef {
+            dim: self.dim(),
+            vectors: self.vectors(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, K: EmbeddingKey + Deserialize<'de>> Deserialize<'de> for Embedding<K> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = EmbeddingData::<K>::deserialize(deserializer)?;
+        let mut emb = Embedding::new(data.dim);
+
+        for (key, vector) in data.vectors {
+            if emb.row(key.clone()).is_some() {
+                return Err(D::Error::custom(format!("duplicate key {:?}", key)));
+            }
+            emb.add_vector(key, &vector).map_err(D::Error::custom)?;
+        }
+
+        Ok(emb)
+    }
+}
+
+/// Only the configuration is stored; a walker without a seed is seeded
+/// from entropy again when deserialized
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "NonBiasedRandomWalker")]
+struct WalkerConfig {
+    walks_per_node: usize,
+    walk_length: usize,
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+impl Serialize for NonBiasedRandomWalker {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WalkerConfig {
+            walks_per_node: self.walks_per_node(),
+            walk_length: self.walk_length(),
+            seed: self.seed(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NonBiasedRandomWalker {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let config = WalkerConfig::deserialize(deserializer)?;
+        let mut walker = NonBiasedRandomWalker::new(config.walks_per_node, config.walk_length);
+
+        if let Some(seed) = config.seed {
+            walker.set_seed(seed);
+        }
+
+        Ok(walker)
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/serialization_tests.rs"]
+mod serialization_tests;
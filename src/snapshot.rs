@@ -0,0 +1,480 @@
+/// Native binary snapshots of networks and embeddings
+use std::convert::TryInto;
+use std::fs;
+use std::io;
+use std::io::{BufRead, Write};
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+use petgraph::visit::EdgeRef;
+
+use crate::embedding::model::*;
+use crate::embedding::reader::EmbeddingReader;
+use crate::embedding::writer::EmbeddingWriter;
+use crate::error::{ErrorKind, ReaderError};
+use crate::network::model::*;
+use crate::network::reader::NetworkReader;
+use crate::network::writer::NetworkWriter;
+
+const MAGIC: &[u8] = b"RNETSNAP";
+
+/// Version written by the snapshot writers, starting at 1. Older snapshots
+/// stay readable, newer ones are rejected with `ErrorKind::VersionMismatch`.
+pub const FORMAT_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Content {
+    Network = 1,
+    Embedding = 2,
+    HnswIndex = 3,
+}
+
+impl Content {
+    fn name(byte: u8) -> &'static str {
+        match byte {
+            b if b == Content::Network as u8 => "a network",
+            b if b == Content::Embedding as u8 => "an embedding",
+            b if b == Content::HnswIndex as u8 => "an HNSW index",
+            _ => "unknown content",
+        }
+    }
+}
+
+/// Little-endian encoding of the node ids, edge data and embedding keys
+/// stored in a snapshot
+pub trait SnapshotValue: Sized {
+    /// Stored in the header, so a snapshot is only read back into the types
+    /// it was written from
+    const TYPE_NAME: &'static str;
+
+    fn encode(&self, buf: &mut Vec<u8>);
+
+    fn decode(input: &mut &[u8]) -> Result<Self, ReaderError>;
+}
+
+macro_rules! impl_snapshot_value {
+    ($($t:ty),*) => {
+        $(
+            impl SnapshotValue for $t {
+                const TYPE_NAME: &'static str = stringify!($t);
+
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> Result<Self, ReaderError> {
+                    let bytes = take(input, std::mem::size_of::<$t>())?;
+                    Ok(<$t>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+        )*
+    };
+}
+
+impl_snapshot_value!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+/// Written as a `u64`, so snapshots are portable across platforms
+impl SnapshotValue for usize {
+    const TYPE_NAME: &'static str = "usize";
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u64).encode(buf);
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, ReaderError> {
+        u64::decode(input)?
+            .try_into()
+            .map_err(|_| ReaderError::malformed("Value out of range"))
+    }
+}
+
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], ReaderError> {
+    if input.len() < len {
+        return Err(ReaderError::malformed("Unexpected end of file"));
+    }
+
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+
+    Ok(bytes)
+}
+
+/// Capacity for `len` decoded elements, bounded by the remaining input so
+/// a corrupted length can't exhaust memory
+pub(crate) fn capacity(len: usize, input: &[u8]) -> usize {
+    len.min(input.len())
+}
+
+fn encode_str(s: &str, buf: &mut Vec<u8>) {
+    s.len().encode(buf);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn decode_str<'a>(input: &mut &'a [u8]) -> Result<&'a str, ReaderError> {
+    let len = usize::decode(input)?;
+    std::str::from_utf8(take(input, len)?)
+        .map_err(|_| ReaderError::malformed("String is not valid UTF-8"))
+}
+
+pub(crate) fn write_snapshot(
+    file: PathBuf,
+    content: Content,
+    types: [&str; 2],
+    payload: &[u8],
+) -> Result<(), io::Error> {
+    let mut header = Vec::new();
+    header.extend_from_slice(MAGIC);
+    FORMAT_VERSION.encode(&mut header);
+    header.push(content as u8);
+    for name in types.iter() {
+        encode_str(name, &mut header);
+    }
+    payload.len().encode(&mut header);
+    crc32fast::hash(payload).encode(&mut header);
+
+    let mut writer = io::BufWriter::new(fs::File::create(file)?);
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+
+    writer.flush()
+}
+
+/// Writes a network snapshot: the `RNETSNAP` magic, the format version, the
+/// kind of content, the names of the stored types, the payload length and
+/// its CRC-32, followed by the payload. The node and edge order, labels and
+/// features are kept; the multi-edge and self-loop policies are not stored.
+#[derive(Default)]
+pub struct NetworkSnapshotWriter {}
+
+impl NetworkSnapshotWriter {
+    pub fn new() -> NetworkSnapshotWriter {
+        NetworkSnapshotWriter {}
+    }
+}
+
+impl<N, E> NetworkWriter<N, E> for NetworkSnapshotWriter
+where
+    N: NetworkNode + SnapshotValue,
+    E: Copy + SnapshotValue,
+{
+    fn write(&self, net: &Network<N, E>, file: PathBuf) -> Result<(), io::Error> {
+        let graph = net.graph();
+        let mut payload = Vec::new();
+
+        payload.push(net.is_directed() as u8);
+
+        graph.node_count().encode(&mut payload);
+        for nx in graph.node_indices() {
+            graph[nx].encode(&mut payload);
+        }
+
+        // Both directions of undirected edges are stored, in index order
+        graph.edge_count().encode(&mut payload);
+        for e in graph.edge_references() {
+            e.source().index().encode(&mut payload);
+            e.target().index().encode(&mut payload);
+            e.weight().encode(&mut payload);
+        }
+
+        let labels: Vec<(usize, &str)> = graph
+            .node_indices()
+            .filter_map(|nx| net.node_label(graph[nx]).map(|l| (nx.index(), l)))
+            .collect();
+        labels.len().encode(&mut payload);
+        for (idx, label) in labels {
+            idx.encode(&mut payload);
+            encode_str(label, &mut payload);
+        }
+
+        let features: Vec<(usize, &NodeFeatures)> = graph
+            .node_indices()
+            .filter_map(|nx| net.node_features(graph[nx]).map(|f| (nx.index(), f)))
+            .collect();
+        features.len().encode(&mut payload);
+        for (idx, f) in features {
+            idx.encode(&mut payload);
+            match f {
+                NodeFeatures::Dense(values) => {
+                    payload.push(0);
+                    values.len().encode(&mut payload);
+                    values.iter().for_each(|v| v.encode(&mut payload));
+                }
+                NodeFeatures::Sparse { dim, entries } => {
+                    payload.push(1);
+                    dim.encode(&mut payload);
+                    entries.len().encode(&mut payload);
+                    for (i, v) in entries.iter() {
+                        i.encode(&mut payload);
+                        v.encode(&mut payload);
+                    }
+                }
+            }
+        }
+
+        write_snapshot(
+            file,
+            Content::Network,
+            [N::TYPE_NAME, E::TYPE_NAME],
+            &payload,
+        )
+    }
+}
+
+/// Writes an embedding snapshot, with the same header as network snapshots
+#[derive(Default)]
+pub struct EmbeddingSnapshotWriter {}
+
+impl EmbeddingSnapshotWriter {
+    pub fn new() -> EmbeddingSnapshotWriter {
+        EmbeddingSnapshotWriter {}
+    }
+}
+
+impl<K: EmbeddingKey + SnapshotValue> EmbeddingWriter<K> for EmbeddingSnapshotWriter {
+    fn write(&self, emb: &Embedding<K>, file: PathBuf) -> Result<(), io::Error> {
+        let mut payload = Vec::new();
+
+        emb.dim().encode(&mut payload);
+        let rows = emb.vectors();
+        rows.len().encode(&mut payload);
+        for (key, vector) in rows {
+            key.encode(&mut payload);
+            for v in vector {
+                v.encode(&mut payload);
+            }
+        }
+
+        write_snapshot(
+            file,
+            Content::Embedding,
+            [K::TYPE_NAME, f64::TYPE_NAME],
+            &payload,
+        )
+    }
+}
+
+/// Checks the header of the snapshot in `data` and returns its payload
+pub(crate) fn read_payload<'a>(
+    data: &'a [u8],
+    content: Content,
+    types: [&str; 2],
+) -> Result<&'a [u8], ReaderError> {
+    let mut input = data;
+
+    if take(&mut input, MAGIC.len()).ok() != Some(MAGIC) {
+        return Err(ReaderError::malformed("Not a snapshot file"));
+    }
+
+    let version = u16::decode(&mut input)?;
+    if version == 0 {
+        return Err(ReaderError::malformed("Unknown format version").with_token("0"));
+    }
+    if version > FORMAT_VERSION {
+        return Err(ReaderError::new(ErrorKind::VersionMismatch {
+            found: version,
+            supported: FORMAT_VERSION,
+        }));
+    }
+
+    let found = u8::decode(&mut input)?;
+    if found != content as u8 {
+        return Err(ReaderError::malformed(&format!(
+            "Snapshot holds {}, expected {}",
+            Content::name(found),
+            Content::name(content as u8)
+        )));
+    }
+
+    for expected in types.iter() {
+        let found = decode_str(&mut input)?;
+        if found != *expected {
+            return Err(ReaderError::malformed(&format!(
+                "Snapshot holds {} values, expected {}",
+                found, expected
+            )));
+        }
+    }
+
+    let len = usize::decode(&mut input)?;
+    let checksum = u32::decode(&mut input)?;
+    let payload = take(&mut input, len)?;
+
+    if !input.is_empty() {
+        return Err(ReaderError::malformed("Trailing data after snapshot"));
+    }
+    if crc32fast::hash(payload) != checksum {
+        return Err(ReaderError::malformed("Checksum mismatch"));
+    }
+
+    Ok(payload)
+}
+
+/// Reads snapshots written by `NetworkSnapshotWriter` into a `Network<N, E>` of the
+/// same node and edge types
+pub struct NetworkSnapshotReader<N, E> {
+    types: PhantomData<(N, E)>,
+}
+
+impl<N, E> Default for NetworkSnapshotReader<N, E> {
+    fn default() -> Self {
+        NetworkSnapshotReader::new()
+    }
+}
+
+impl<N, E> NetworkSnapshotReader<N, E> {
+    pub fn new() -> NetworkSnapshotReader<N, E> {
+        NetworkSnapshotReader { types: PhantomData }
+    }
+}
+
+impl<N, E> NetworkReader for NetworkSnapshotReader<N, E>
+where
+    N: NetworkNode + SnapshotValue,
+    E: Copy + SnapshotValue,
+{
+    type N = N;
+    type E = E;
+
+    fn read_from<R: BufRead>(&self, mut reader: R) -> Result<Network<N, E>, ReaderError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        let mut input = read_payload(&data, Content::Network, [N::TYPE_NAME, E::TYPE_NAME])?;
+        let input = &mut input;
+
+        let mut net = Network::new(u8::decode(input)? != 0);
+
+        let num_nodes = usize::decode(input)?;
+        let mut nodes = Vec::with_capacity(capacity(num_nodes, input));
+        for _ in 0..num_nodes {
+            let node = N::decode(input)?;
+            net.add_node(node);
+            nodes.push(node);
+        }
+
+        if net.num_nodes() != nodes.len() {
+            return Err(ReaderError::malformed("Duplicate node in snapshot"));
+        }
+
+        let node_at = |input: &mut &[u8]| -> Result<N, ReaderError> {
+            nodes
+                .get(usize::decode(input)?)
+                .copied()
+                .ok_or_else(|| ReaderError::malformed("Node index out of range"))
+        };
+
+        for _ in 0..usize::decode(input)? {
+            let from = node_at(input)?;
+            let to = node_at(input)?;
+            net.restore_edge(from, to, E::decode(input)?);
+        }
+
+        for _ in 0..usize::decode(input)? {
+            let node = node_at(input)?;
+            net.set_node_label(node, decode_str(input)?);
+        }
+
+        for _ in 0..usize::decode(input)? {
+            let node = node_at(input)?;
+            let features = match u8::decode(input)? {
+                0 => {
+                    let len = usize::decode(input)?;
+                    let mut values = Vec::with_capacity(capacity(len, input));
+                    for _ in 0..len {
+                        values.push(f64::decode(input)?);
+                    }
+                    NodeFeatures::Dense(values.into())
+                }
+                1 => {
+                    let dim = usize::decode(input)?;
+                    let len = usize::decode(input)?;
+                    let mut entries = Vec::with_capacity(capacity(len, input));
+                    for _ in 0..len {
+                        entries.push((usize::decode(input)?, f64::decode(input)?));
+                    }
+                    NodeFeatures::Sparse { dim, entries }
+                }
+                _ => return Err(ReaderError::malformed("Unknown features layout")),
+            };
+            net.set_node_features(node, features)?;
+        }
+
+        if !input.is_empty() {
+            return Err(ReaderError::malformed("Trailing data in payload"));
+        }
+
+        Ok(net)
+    }
+}
+
+/// Reads snapshots written by `EmbeddingSnapshotWriter`, with `usize` keys
+/// unless created with `with_key_type`
+pub struct EmbeddingSnapshotReader<K = usize> {
+    key_type: PhantomData<K>,
+}
+
+impl Default for EmbeddingSnapshotReader {
+    fn default() -> Self {
+        EmbeddingSnapshotReader::new()
+    }
+}
+
+impl EmbeddingSnapshotReader {
+    pub fn new() -> EmbeddingSnapshotReader {
+        EmbeddingSnapshotReader::with_key_type()
+    }
+}
+
+impl<K> EmbeddingSnapshotReader<K> {
+    pub fn with_key_type() -> EmbeddingSnapshotReader<K> {
+        EmbeddingSnapshotReader {
+            key_type: PhantomData,
+        }
+    }
+}
+
+fn decode_embedding<K: EmbeddingKey + SnapshotValue>(
+    data: &[u8],
+) -> Result<Embedding<K>, ReaderError> {
+    let mut input = read_payload(data, Content::Embedding, [K::TYPE_NAME, f64::TYPE_NAME])?;
+    let input = &mut input;
+
+    let dim = usize::decode(input)?;
+    let mut emb = Embedding::new(dim);
+    let mut vector = Vec::with_capacity(capacity(dim, input));
+
+    for _ in 0..usize::decode(input)? {
+        let key = K::decode(input)?;
+        if emb.row(key.clone()).is_some() {
+            return Err(ReaderError::malformed("Duplicate key in snapshot"));
+        }
+
+        vector.clear();
+        for _ in 0..dim {
+            vector.push(f64::decode(input)?);
+        }
+
+        emb.add_vector(key, &vector)?;
+    }
+
+    if !input.is_empty() {
+        return Err(ReaderError::malformed("Trailing data in payload"));
+    }
+
+    Ok(emb)
+}
+
+impl<K: EmbeddingKey + SnapshotValue> EmbeddingReader for EmbeddingSnapshotReader<K> {
+    type K = K;
+
+    fn read_from<R: BufRead>(&self, mut reader: R) -> Result<Embedding<K>, ReaderError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        decode_embedding(&data)
+    }
+}
+
+#[cfg(test)]
+#[path = "../tests/unit/snapshot_tests.rs"]
+mod snapshot_tests;
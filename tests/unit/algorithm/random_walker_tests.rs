@@ -13,7 +13,7 @@ fn run_nonbiased_rw(
     let mut rw = NonBiasedRandomWalker::new(walk_per_node, walk_length);
     rw.set_seed(seed);
 
-    let walk = rw.walk(&net, start_node);
+    let walk = rw.walk(net, start_node);
 
     assert_eq!(walk, expected_walk);
 }
@@ -44,7 +44,7 @@ fn test_nonbiased_rw_single_walk_multi_node_connected_directed() {
     net.add_node("A");
     net.add_node("B");
 
-    net.add_edge("A", "B", 1.0);
+    net.add_edge("A", "B", 1.0).unwrap();
 
     run_nonbiased_rw(&net, 0, 1, 4, "A", &["A", "B"]);
 }
@@ -56,7 +56,7 @@ fn test_nonbiased_rw_single_walk_multi_node_connected_undirected() {
     net.add_node("A");
     net.add_node("B");
 
-    net.add_edge("A", "B", 1.0);
+    net.add_edge("A", "B", 1.0).unwrap();
 
     run_nonbiased_rw(&net, 0, 1, 4, "A", &["A", "B", "A", "B"]);
 }
@@ -70,9 +70,9 @@ fn test_nonbiased_rw_single_walk_multi_node_seed_path_directed() {
     net.add_node("C");
     net.add_node("D");
 
-    net.add_edge("A", "B", 1.0);
-    net.add_edge("B", "C", 1.0);
-    net.add_edge("A", "D", 1.0);
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+    net.add_edge("A", "D", 1.0).unwrap();
 
     run_nonbiased_rw(&net, 1, 1, 3, "A", &["A", "B", "C"]);
 }
@@ -0,0 +1,97 @@
+/// Unit tests for transparent decompression
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::compression::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "utils.rs"]
+mod utils;
+
+use crate::compression::compression_tests::utils::*;
+
+fn read_all(file: &str) -> String {
+    let mut content = String::new();
+    open(Path::new(file))
+        .unwrap()
+        .read_to_string(&mut content)
+        .unwrap();
+
+    content
+}
+
+#[test]
+fn test_detect_by_magic_bytes() {
+    let detect = |file: &str| Compression::detect(Path::new(file)).unwrap();
+
+    assert_eq!(detect("resources/nets/simple-net.txt"), Compression::None);
+    assert_eq!(
+        detect("resources/nets/simple-net.txt.gz"),
+        Compression::Gzip
+    );
+    assert_eq!(
+        detect("resources/nets/simple-net.txt.bz2"),
+        Compression::Bzip2
+    );
+    assert_eq!(
+        detect("resources/nets/simple-net.txt.zst"),
+        Compression::Zstd
+    );
+    assert_eq!(
+        detect("resources/nets/simple-net-gzip-no-extension"),
+        Compression::Gzip
+    );
+}
+
+#[test]
+fn test_text_starting_like_bzip2() {
+    let file = tmp_file("bzh.txt");
+    fs::write(&file, "BZh9 1 2\n").unwrap();
+
+    assert_eq!(Compression::detect(&file).unwrap(), Compression::None);
+    assert_eq!(read_all(file.to_str().unwrap()), "BZh9 1 2\n");
+
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn test_detect_by_extension() {
+    for (name, expected) in &[
+        ("plain.gz", Compression::Gzip),
+        ("plain.bz2", Compression::Bzip2),
+        ("plain.zst", Compression::Zstd),
+    ] {
+        let file = tmp_file(name);
+        fs::write(&file, "0 1\n").unwrap();
+
+        assert_eq!(Compression::detect(&file).unwrap(), *expected);
+
+        fs::remove_file(&file).unwrap();
+    }
+
+    assert_eq!(
+        Compression::detect(Path::new("resources/embs/empty.txt")).unwrap(),
+        Compression::None
+    );
+    assert!(Compression::detect(Path::new("/tmp/non-existing-file.gz")).is_err());
+}
+
+#[test]
+fn test_decompressed_content() {
+    let plain = read_all("resources/nets/simple-net.txt");
+
+    assert_eq!(read_all("resources/nets/simple-net.txt.gz"), plain);
+    assert_eq!(read_all("resources/nets/simple-net.txt.bz2"), plain);
+    assert_eq!(read_all("resources/nets/simple-net.txt.zst"), plain);
+}
+
+#[test]
+fn test_corrupted_archive() {
+    let mut content = String::new();
+    let res = open(Path::new("resources/nets/corrupted.txt.gz"))
+        .unwrap()
+        .read_to_string(&mut content);
+
+    assert!(res.is_err());
+}
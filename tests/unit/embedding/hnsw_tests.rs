@@ -0,0 +1,270 @@
+/// Unit tests for the HNSW index
+use std::collections::HashSet;
+use std::fs;
+
+use rand::prelude::*;
+
+use crate::embedding::hnsw::*;
+use crate::embedding::similarity::Metric;
+use crate::embedding::writer::EmbeddingWriter;
+use crate::error::ErrorKind;
+use crate::snapshot::{write_snapshot, Content, EmbeddingSnapshotWriter, SnapshotValue};
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::embedding::hnsw::hnsw_tests::utils::*;
+
+fn random_vector(rng: &mut StdRng, dim: usize) -> Vec<f64> {
+    (0..dim).map(|_| rng.gen_range(-1.0, 1.0)).collect()
+}
+
+fn random_embedding(seed: u64, num_vectors: usize, dim: usize) -> Embedding<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut emb = Embedding::new(dim);
+
+    for key in 0..num_vectors {
+        emb.add_vector(key, &random_vector(&mut rng, dim)).unwrap();
+    }
+
+    emb
+}
+
+fn build_index(emb: &Embedding<usize>, metric: Metric) -> HnswIndex<usize> {
+    let mut index = HnswIndex::new(emb.dim(), metric);
+    index.set_m(8);
+    index.set_ef_construction(64);
+    index.set_seed(42);
+
+    index.insert_embedding(emb).unwrap();
+
+    index
+}
+
+/// Fraction of the exact `k` nearest neighbours found by the index
+fn recall(emb: &Embedding<usize>, index: &HnswIndex<usize>, queries: &[Vec<f64>], k: usize) -> f64 {
+    let mut found = 0;
+
+    for query in queries.iter() {
+        let exact: HashSet<usize> = emb
+            .most_similar_to_vector(query, k, index.metric())
+            .unwrap()
+            .into_iter()
+            .map(|(key, _)| *key)
+            .collect();
+
+        found += index
+            .search(query, k)
+            .unwrap()
+            .into_iter()
+            .filter(|(key, _)| exact.contains(*key))
+            .count();
+    }
+
+    found as f64 / (queries.len() * k) as f64
+}
+
+#[test]
+fn test_recall_against_exact_search() {
+    let emb = random_embedding(0, 500, 8);
+    let mut rng = StdRng::seed_from_u64(1);
+    let queries: Vec<Vec<f64>> = (0..50).map(|_| random_vector(&mut rng, 8)).collect();
+
+    for metric in [Metric::Cosine, Metric::Dot, Metric::Euclidean].iter() {
+        let index = build_index(&emb, *metric);
+
+        assert_eq!(index.len(), 500);
+        assert!(recall(&emb, &index, &queries, 10) >= 0.9);
+    }
+}
+
+#[test]
+fn test_search_scores() {
+    let emb = random_embedding(2, 100, 4);
+    let index = build_index(&emb, Metric::Cosine);
+    let query = [0.5, -0.5, 0.25, 1.0];
+
+    let found = index.search(&query, 5).unwrap();
+    assert_eq!(found.len(), 5);
+
+    for (key, score) in found.iter() {
+        let expected = Metric::Cosine.between(&query, emb.get_vector(**key).unwrap());
+        assert!((score - expected).abs() < 1e-12);
+    }
+    for pair in found.windows(2) {
+        assert!(pair[0].1 >= pair[1].1);
+    }
+}
+
+#[test]
+fn test_incremental_insertion() {
+    let emb = random_embedding(3, 300, 6);
+    let mut index = build_index(&random_embedding(3, 150, 6), Metric::Euclidean);
+
+    for key in 150..300 {
+        index.insert(key, emb.get_vector(key).unwrap()).unwrap();
+    }
+
+    assert_eq!(index.len(), 300);
+    for key in (0..300).step_by(7) {
+        let found = index.search(emb.get_vector(key).unwrap(), 1).unwrap();
+        assert_eq!(found, vec![(&key, 0.0)]);
+    }
+}
+
+#[test]
+fn test_insert_embedding() {
+    let emb = random_embedding(4, 50, 3);
+    let index = build_index(&emb, Metric::Dot);
+
+    assert_eq!(index.len(), 50);
+    assert_eq!(index.dim(), 3);
+    assert!(index.contains(&49));
+    assert!(!index.contains(&50));
+    assert_eq!(index.search(&[1.0, 0.0, 0.0], 60).unwrap().len(), 50);
+
+    // The same seed builds the same graph
+    let other = build_index(&emb, Metric::Dot);
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..10 {
+        let query = random_vector(&mut rng, 3);
+        assert_eq!(other.search(&query, 5), index.search(&query, 5));
+    }
+
+    let mut index = HnswIndex::new(2, Metric::Dot);
+    assert_eq!(
+        index.insert_embedding(&emb).unwrap_err(),
+        EmbeddingError::WrongEmbeddingDimension("Expected: 2 Got: 3".to_string())
+    );
+}
+
+#[test]
+fn test_empty_index() {
+    let index: HnswIndex<usize> = HnswIndex::new(2, Metric::Cosine);
+
+    assert!(index.is_empty());
+    assert!(index.search(&[1.0, 0.0], 3).unwrap().is_empty());
+}
+
+#[test]
+fn test_insertion_errors() {
+    let mut index = HnswIndex::new(2, Metric::Euclidean);
+    index.insert("a", &[1.0, 0.0]).unwrap();
+
+    assert_eq!(
+        index.insert("a", &[0.0, 1.0]).unwrap_err(),
+        EmbeddingError::DuplicateKey("Key \"a\" is already indexed".to_string())
+    );
+    assert_eq!(
+        index.insert("b", &[0.0]).unwrap_err(),
+        EmbeddingError::WrongEmbeddingDimension("Expected: 2 Got: 1".to_string())
+    );
+    assert_eq!(
+        index.search(&[0.0, 1.0, 2.0], 1).unwrap_err(),
+        EmbeddingError::WrongEmbeddingDimension("Expected: 2 Got: 3".to_string())
+    );
+    assert_eq!(index.len(), 1);
+}
+
+#[test]
+fn test_save_and_load() {
+    let emb = random_embedding(5, 200, 4);
+    let mut index = build_index(&emb, Metric::Cosine);
+    index.set_ef_search(20);
+
+    let file = tmp_file("hnsw-round-trip.snap");
+    index.save(file.clone()).unwrap();
+    let mut loaded: HnswIndex<usize> = HnswIndex::load(file.clone()).unwrap();
+
+    assert_eq!(loaded.len(), 200);
+    assert_eq!(loaded.metric(), Metric::Cosine);
+    assert_eq!(loaded.m(), 8);
+    assert_eq!(loaded.ef_construction(), 64);
+    assert_eq!(loaded.ef_search(), 20);
+
+    let mut rng = StdRng::seed_from_u64(6);
+    for _ in 0..20 {
+        let query = random_vector(&mut rng, 4);
+        assert_eq!(loaded.search(&query, 5), index.search(&query, 5));
+    }
+
+    // Loaded indices keep growing
+    loaded.insert(200, &[1.0, 1.0, 1.0, 1.0]).unwrap();
+    assert_eq!(loaded.search(&[2.0, 2.0, 2.0, 2.0], 1).unwrap()[0].0, &200);
+
+    let err = HnswIndex::<u32>::load(file.clone()).unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Snapshot holds usize values, expected u32".to_string())
+    );
+    assert_eq!(err.path(), Some(file.as_path()));
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn test_load_other_snapshot() {
+    let file = tmp_file("hnsw-embedding.snap");
+    EmbeddingSnapshotWriter::new()
+        .write(&random_embedding(8, 10, 2), file.clone())
+        .unwrap();
+
+    let err = HnswIndex::<usize>::load(file.clone()).unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat(
+            "Snapshot holds an embedding, expected an HNSW index".to_string()
+        )
+    );
+
+    fs::write(&file, b"0 1\n").unwrap();
+    let err = HnswIndex::<usize>::load(file.clone()).unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not a snapshot file".to_string())
+    );
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn test_load_entry_point_below_top_layer() {
+    let mut payload = Vec::new();
+    (Metric::Dot as u8).encode(&mut payload);
+    1usize.encode(&mut payload);
+    for param in [8usize, 64, 16].iter() {
+        param.encode(&mut payload);
+    }
+    // Entry point 0 is only on layer 0, node 1 reaches layer 1
+    0usize.encode(&mut payload);
+    2usize.encode(&mut payload);
+    for (key, layers) in [(0usize, vec![vec![1usize]]), (1, vec![vec![0], vec![]])].iter() {
+        key.encode(&mut payload);
+        1.0f64.encode(&mut payload);
+        layers.len().encode(&mut payload);
+        for neighbours in layers.iter() {
+            neighbours.len().encode(&mut payload);
+            for neighbour in neighbours.iter() {
+                neighbour.encode(&mut payload);
+            }
+        }
+    }
+
+    let file = tmp_file("hnsw-entry-point.snap");
+    write_snapshot(
+        file.clone(),
+        Content::HnswIndex,
+        [usize::TYPE_NAME, f64::TYPE_NAME],
+        &payload,
+    )
+    .unwrap();
+
+    let err = HnswIndex::<usize>::load(file.clone()).unwrap_err();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Entry point is not on the top layer".to_string())
+    );
+}
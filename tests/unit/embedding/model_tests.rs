@@ -1,5 +1,6 @@
 /// Unit tests for embedding data structure
 use crate::embedding::model::*;
+use crate::embedding::similarity::Metric;
 
 #[test]
 fn test_add_vector_wrong_dim() {
@@ -21,7 +22,8 @@ fn test_add_vector_proper_dim() {
     let res = emb.add_vector("A", &[1.0, 2.0, 3.0]);
     assert!(res.is_ok(), "Adding embedding vector should succeed");
 
-    let vector = emb.emb_vectors.get("A").unwrap().as_slice().unwrap();
+    let row = emb.emb_rows["A"];
+    let vector = emb.emb_vectors.row(row).to_vec();
     assert_eq!(vector, &[1.0, 2.0, 3.0], "Embedding vector should be added");
 }
 
@@ -91,3 +93,136 @@ fn test_get_keys_nonempty() {
     assert!(keys.contains(&&"A"), "Vector \"A\" should be added");
     assert!(keys.contains(&&"B"), "Vector \"B\" should be added");
 }
+
+#[test]
+fn test_matrix_rows_follow_insertion_order() {
+    let mut emb: Embedding<&str> = Embedding::new(2);
+    emb.add_vector("B", &[1.0, 2.0]).unwrap();
+    emb.add_vector("A", &[3.0, 4.0]).unwrap();
+    emb.add_vector("C", &[5.0, 6.0]).unwrap();
+
+    assert_eq!(emb.keys(), vec![&"B", &"A", &"C"]);
+    assert_eq!(emb.row("A"), Some(1));
+    assert_eq!(emb.row("D"), None);
+    assert_eq!(emb.as_matrix(), arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]));
+    assert_eq!(
+        emb.vectors(),
+        vec![
+            (&"B", &[1.0, 2.0][..]),
+            (&"A", &[3.0, 4.0][..]),
+            (&"C", &[5.0, 6.0][..])
+        ]
+    );
+}
+
+#[test]
+fn test_add_vector_replaces_existing_row() {
+    let mut emb: Embedding<&str> = Embedding::new(2);
+    emb.add_vector("A", &[1.0, 2.0]).unwrap();
+    emb.add_vector("B", &[3.0, 4.0]).unwrap();
+    emb.add_vector("A", &[-1.0, -2.0]).unwrap();
+
+    assert_eq!(emb.keys().len(), 2);
+    assert_eq!(emb.row("A"), Some(0));
+    assert_eq!(emb.get_vector("A").unwrap(), &[-1.0, -2.0]);
+    assert_eq!(emb.as_matrix().dim(), (2, 2));
+}
+
+#[test]
+fn test_empty_matrix() {
+    let emb: Embedding<usize> = Embedding::new(4);
+
+    assert_eq!(emb.as_matrix().dim(), (0, 4));
+}
+
+fn similarity_embedding() -> Embedding<&'static str> {
+    let mut emb: Embedding<&str> = Embedding::new(2);
+    emb.add_vector("east", &[1.0, 0.0]).unwrap();
+    emb.add_vector("north-east", &[1.0, 1.0]).unwrap();
+    emb.add_vector("north", &[0.0, 2.0]).unwrap();
+    emb.add_vector("west", &[-3.0, 0.0]).unwrap();
+
+    emb
+}
+
+#[test]
+fn test_similarity() {
+    let emb = similarity_embedding();
+
+    assert!((emb.similarity("east", "north", Metric::Cosine).unwrap()).abs() < 1e-12);
+    assert_eq!(emb.similarity("east", "west", Metric::Dot).unwrap(), -3.0);
+    assert_eq!(
+        emb.similarity("east", "west", Metric::Euclidean).unwrap(),
+        4.0
+    );
+    assert_eq!(
+        emb.similarity("east", "south", Metric::Cosine).unwrap_err(),
+        EmbeddingError::VectorNotFound("No vector found for \"south\"".to_string())
+    );
+}
+
+#[test]
+fn test_most_similar() {
+    let emb = similarity_embedding();
+
+    fn keys(similar: Vec<(&&'static str, f64)>) -> Vec<&'static str> {
+        similar.into_iter().map(|(k, _)| *k).collect()
+    }
+
+    assert_eq!(
+        keys(emb.most_similar("east", 2, Metric::Cosine).unwrap()),
+        vec!["north-east", "north"]
+    );
+    assert_eq!(
+        keys(emb.most_similar("east", 3, Metric::Dot).unwrap()),
+        vec!["north-east", "north", "west"]
+    );
+    assert_eq!(
+        keys(emb.most_similar("north", 10, Metric::Euclidean).unwrap()),
+        vec!["north-east", "east", "west"]
+    );
+    assert!(emb.most_similar("south", 1, Metric::Dot).is_err());
+
+    assert_eq!(
+        keys(emb.most_similar("west", usize::MAX, Metric::Dot).unwrap()),
+        vec!["north", "east", "north-east"]
+    );
+}
+
+#[test]
+fn test_most_similar_scores_of_large_vectors() {
+    let mut emb = Embedding::new(2);
+    emb.add_vector(0, &[1e4, 1e4]).unwrap();
+    emb.add_vector(1, &[1e4, 1e4 + 1e-3]).unwrap();
+    emb.add_vector(2, &[-1e4, 1e4]).unwrap();
+
+    for metric in [Metric::Cosine, Metric::Dot, Metric::Euclidean].iter() {
+        let similar = emb.most_similar(0, 2, *metric).unwrap();
+        let found = emb.most_similar_to_vector(&[1e4, 1e4], 3, *metric).unwrap();
+
+        assert_eq!(similar.len(), 2);
+        assert_eq!(found.len(), 3);
+        for (key, score) in similar.into_iter().chain(found) {
+            assert_eq!(score, emb.similarity(0, *key, *metric).unwrap());
+        }
+    }
+    assert!((emb.similarity(0, 1, Metric::Euclidean).unwrap() - 1e-3).abs() < 1e-9);
+}
+
+#[test]
+fn test_most_similar_to_vector() {
+    let emb = similarity_embedding();
+
+    let similar = emb
+        .most_similar_to_vector(&[0.9, 0.1], 2, Metric::Euclidean)
+        .unwrap();
+    assert_eq!(similar.len(), 2);
+    assert_eq!(*similar[0].0, "east");
+    assert!((similar[0].1 - Metric::Euclidean.between(&[0.9, 0.1], &[1.0, 0.0])).abs() < 1e-12);
+
+    assert_eq!(
+        emb.most_similar_to_vector(&[1.0], 1, Metric::Cosine)
+            .unwrap_err(),
+        EmbeddingError::WrongEmbeddingDimension("Expected: 2 Got: 1".to_string())
+    );
+}
@@ -0,0 +1,135 @@
+/// Unit tests for the NumPy array format
+use std::io::Cursor;
+
+use crate::embedding::npy::*;
+use crate::error::ErrorKind;
+
+#[test]
+fn test_header_is_aligned() {
+    let mut buf: Vec<u8> = Vec::new();
+    write_matrix(&mut buf, 2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+
+    assert_eq!(&buf[..8], b"\x93NUMPY\x01\x00");
+    assert_eq!((buf.len() - 6 * 8) % 64, 0);
+
+    let header_len = u16::from_le_bytes([buf[8], buf[9]]) as usize;
+    let header = String::from_utf8(buf[10..10 + header_len].to_vec()).unwrap();
+    assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (2, 3), }"));
+    assert!(header.ends_with('\n'));
+}
+
+#[test]
+fn test_matrix_round_trip() {
+    let mut buf: Vec<u8> = Vec::new();
+    write_matrix(&mut buf, 2, 2, &[0.1, -2.0, 3.5, 1e-12]).unwrap();
+
+    let array = read_array(&mut Cursor::new(buf)).unwrap();
+
+    assert_eq!(
+        array,
+        NpyArray {
+            shape: vec![2, 2],
+            data: NpyData::Float(vec![0.1, -2.0, 3.5, 1e-12]),
+        }
+    );
+}
+
+#[test]
+fn test_strings_round_trip() {
+    let keys = vec!["a".to_string(), "żółw".to_string(), "".to_string()];
+
+    let mut buf: Vec<u8> = Vec::new();
+    write_strings(&mut buf, &keys).unwrap();
+
+    let array = read_array(&mut Cursor::new(buf)).unwrap();
+
+    assert_eq!(array.shape, vec![3]);
+    assert_eq!(array.data, NpyData::Unicode(keys));
+}
+
+#[test]
+fn test_fortran_order_f32() {
+    let mut file = std::fs::File::open("resources/embs/fortran-f32.npy").unwrap();
+
+    let array = read_array(&mut file).unwrap();
+
+    assert_eq!(array.shape, vec![2, 3]);
+    assert_eq!(
+        array.data,
+        NpyData::Float(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])
+    );
+}
+
+#[test]
+fn test_not_npy() {
+    let err = read_array(&mut Cursor::new(b"2 3\n0 1.0 2.0 3.0\n".to_vec())).unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not a .npy file".to_string())
+    );
+}
+
+#[test]
+fn test_truncated_data() {
+    let mut buf: Vec<u8> = Vec::new();
+    write_matrix(&mut buf, 2, 2, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+    buf.truncate(buf.len() - 1);
+
+    let err = read_array(&mut Cursor::new(buf)).unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unexpected end of file".to_string())
+    );
+}
+
+fn header_only(descr: &str, shape: &[usize]) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::new();
+    write_header(&mut buf, descr, shape).unwrap();
+
+    buf
+}
+
+#[test]
+fn test_oversized_shape() {
+    for (descr, shape) in [
+        ("<f8", vec![usize::MAX, 2]),
+        ("<f8", vec![1 << 31, 1 << 31]),
+    ]
+    .iter()
+    {
+        let err = read_array(&mut Cursor::new(header_only(descr, shape))).unwrap_err();
+
+        assert_eq!(
+            *err.kind(),
+            ErrorKind::MalformedFileFormat("Array shape is too large".to_string())
+        );
+    }
+}
+
+#[test]
+fn test_shape_larger_than_input() {
+    let mut buf = header_only("<f8", &[1_000_000_000, 1000]);
+    buf.extend_from_slice(&1.0f64.to_le_bytes());
+
+    let err = read_array(&mut Cursor::new(buf)).unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unexpected end of file".to_string())
+    );
+}
+
+#[test]
+fn test_invalid_string_width() {
+    for descr in ["<U0", "<U4611686018427387904"].iter() {
+        let err = read_array(&mut Cursor::new(header_only(descr, &[2]))).unwrap_err();
+
+        assert_eq!(
+            *err.kind(),
+            ErrorKind::MalformedFileFormat("Unsupported .npy data type".to_string())
+        );
+        assert_eq!(err.token(), Some(*descr));
+    }
+}
@@ -4,13 +4,19 @@ use std::path::PathBuf;
 use crate::embedding::model::Embedding;
 use crate::embedding::reader::*;
 
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::embedding::reader::reader_tests::utils::*;
+
 #[test]
 fn test_reading_non_existing_file() {
     let reader = W2VEmbeddingVectorsReader::new();
     let path: PathBuf = "/tmp/non-existing-file.txt".parse().unwrap();
 
     let res = reader.read(path);
-    assert_eq!(res.unwrap_err(), ReaderError::FileNotFound);
+    assert_eq!(*res.unwrap_err().kind(), ErrorKind::FileNotFound);
 }
 
 #[test]
@@ -47,8 +53,8 @@ fn test_malformed_header() {
 
     let res = reader.read(path);
     assert_eq!(
-        res.unwrap_err(),
-        ReaderError::MalformedFileFormat(
+        *res.unwrap_err().kind(),
+        ErrorKind::MalformedFileFormat(
             "Header should contain 2 elements: number of nodes and embedding dimension".to_string()
         )
     );
@@ -61,8 +67,8 @@ fn test_malformed_line() {
 
     let res = reader.read(path);
     assert_eq!(
-        res.unwrap_err(),
-        ReaderError::MalformedFileFormat("Not enough elements".to_string())
+        *res.unwrap_err().kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
     );
 }
 
@@ -73,8 +79,8 @@ fn test_malformed_vector() {
 
     let res = reader.read(path);
     assert_eq!(
-        res.unwrap_err(),
-        ReaderError::MalformedFileFormat("Couldn\'t parse float".to_string())
+        *res.unwrap_err().kind(),
+        ErrorKind::MalformedFileFormat("Couldn\'t parse float".to_string())
     );
 }
 
@@ -90,3 +96,512 @@ fn test_read_fb_forum_embedding() {
     assert_eq!(emb.dim(), 128, "Embedding should have dimension 128");
     assert_eq!(emb.keys().len(), 390, "Embedding should contain 390 nodes");
 }
+
+#[test]
+fn test_empty_file() {
+    let reader = W2VEmbeddingVectorsReader::new();
+
+    let err = reader
+        .read("resources/embs/empty.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Missing header".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_truncated_file() {
+    let reader = W2VEmbeddingVectorsReader::new();
+    let path: PathBuf = "resources/embs/truncated.txt".parse().unwrap();
+
+    let err = reader.read(path.clone()).unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
+    );
+    assert_eq!(err.path(), Some(path.as_path()));
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.token(), Some("1 3.0 2"));
+}
+
+#[test]
+fn test_malformed_vector_location() {
+    let reader = W2VEmbeddingVectorsReader::new();
+
+    let err = reader
+        .read("resources/embs/malformed-vector.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert!(err.line().is_some());
+    assert!(err.token().is_some());
+    assert!(err
+        .to_string()
+        .starts_with("resources/embs/malformed-vector.txt:"));
+}
+
+#[test]
+fn test_lenient_skips_malformed_rows() {
+    let reader = W2VEmbeddingVectorsReader::new();
+
+    let (emb, report) = reader
+        .read_lenient("resources/embs/partially-malformed.txt".parse().unwrap(), 5)
+        .unwrap();
+
+    assert_eq!(emb.keys().len(), 2);
+    assert!(emb.keys().contains(&&0));
+    assert!(emb.keys().contains(&&2));
+
+    let lines: Vec<Option<usize>> = report.skipped().iter().map(|e| e.line()).collect();
+    assert_eq!(lines, vec![Some(3), Some(5)]);
+}
+
+#[test]
+fn test_lenient_limit_exceeded() {
+    let reader = W2VEmbeddingVectorsReader::new();
+
+    let err = reader
+        .read_lenient("resources/embs/partially-malformed.txt".parse().unwrap(), 1)
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(5));
+    assert_eq!(err.token(), Some("A"));
+}
+
+#[test]
+fn test_lenient_malformed_header() {
+    let reader = W2VEmbeddingVectorsReader::new();
+
+    let err = reader
+        .read_lenient("resources/embs/malformed-header.txt".parse().unwrap(), 5)
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_reading_binary_file() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let emb = reader
+        .read("resources/embs/simple-embedding.bin".parse().unwrap())
+        .unwrap();
+
+    assert_eq!(emb.dim(), 3);
+    assert_eq!(emb.keys().len(), 2);
+    assert_eq!(*emb.get_vector(0).unwrap(), [1.0, 2.0, 3.0]);
+    assert_eq!(*emb.get_vector(1).unwrap(), [3.0, 2.0, 1.0]);
+}
+
+#[test]
+fn test_reading_binary_file_string_keys() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let (emb, vocab) = reader
+        .read_interned("resources/embs/string-keys.bin".parse().unwrap())
+        .unwrap();
+
+    assert_eq!(emb.dim(), 2);
+    assert_eq!(vocab.len(), 3);
+
+    let decoded = vocab.decode_embedding(&emb).unwrap();
+    assert_eq!(*decoded.get_vector("alice").unwrap(), [1.0, 0.5]);
+    assert_eq!(*decoded.get_vector("bob").unwrap(), [-1.0, 2.0]);
+    assert_eq!(*decoded.get_vector("carol").unwrap(), [0.25, 4.0]);
+}
+
+#[test]
+fn test_reading_binary_file_non_integer_keys() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/string-keys.bin".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string())
+    );
+    assert_eq!(err.token(), Some("alice"));
+}
+
+#[test]
+fn test_reading_truncated_binary_file() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/truncated.bin".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unexpected end of file".to_string())
+    );
+    assert_eq!(err.token(), Some("1"));
+}
+
+#[test]
+fn test_reading_binary_oversized_dimension() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let err = reader
+        .read_from("1 4611686018427387904\n0 ".as_bytes())
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Embedding dimension is too large".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+
+    // Fits in memory arithmetic, but not in the input
+    let err = reader
+        .read_from(&b"1 1000000000000\n0 \x00\x00\x80\x3f"[..])
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unexpected end of file".to_string())
+    );
+    assert_eq!(err.token(), Some("0"));
+}
+
+#[test]
+fn test_reading_binary_malformed_header() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/malformed-header.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_reading_empty_binary_file() {
+    let reader = W2VBinaryEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/empty.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Missing header".to_string())
+    );
+}
+
+#[test]
+fn test_reading_npy_fortran_order() {
+    let reader = NpyEmbeddingReader::new("resources/embs/fortran-f32.keys".into());
+
+    let emb = reader
+        .read("resources/embs/fortran-f32.npy".into())
+        .unwrap();
+
+    assert_eq!(emb.dim(), 3);
+    assert_eq!(*emb.get_vector(10).unwrap(), [1.0, 2.0, 3.0]);
+    assert_eq!(*emb.get_vector(20).unwrap(), [4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn test_reading_npy_missing_keys_file() {
+    let reader = NpyEmbeddingReader::new("/tmp/non-existing-file.keys".into());
+
+    let err = reader
+        .read("resources/embs/fortran-f32.npy".into())
+        .unwrap_err();
+
+    assert_eq!(*err.kind(), ErrorKind::FileNotFound);
+    assert_eq!(
+        err.path(),
+        Some(PathBuf::from("/tmp/non-existing-file.keys").as_path())
+    );
+}
+
+#[test]
+fn test_reading_npy_keys_mismatch() {
+    let reader = NpyEmbeddingReader::new("resources/embs/simple-embedding.txt".into());
+
+    let err = reader
+        .read("resources/embs/fortran-f32.npy".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Expected 2 keys, got 3".to_string())
+    );
+}
+
+#[test]
+fn test_reading_npz_int_keys() {
+    let reader = NpzEmbeddingReader::new();
+
+    let emb = reader.read("resources/embs/int-keys.npz".into()).unwrap();
+
+    assert_eq!(emb.dim(), 2);
+    assert_eq!(*emb.get_vector(7).unwrap(), [0.5, 1.5]);
+    assert_eq!(*emb.get_vector(3).unwrap(), [-2.0, 4.0]);
+}
+
+#[test]
+fn test_reading_compressed_npz() {
+    let reader = NpzEmbeddingReader::new();
+
+    let emb = reader.read("resources/embs/compressed.npz".into()).unwrap();
+
+    assert_eq!(*emb.get_vector(3).unwrap(), [-2.0, 4.0]);
+}
+
+#[test]
+fn test_reading_npz_missing_keys() {
+    let reader = NpzEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/missing-keys.npz".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat(
+            "Archive should contain vectors.npy and keys.npy".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_reading_npz_not_an_archive() {
+    let reader = NpzEmbeddingReader::new();
+
+    let err = reader
+        .read("resources/embs/simple-embedding.txt".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not a valid .npz archive".to_string())
+    );
+}
+
+#[test]
+fn test_reading_compressed_files() {
+    for file in [
+        "resources/embs/simple-embedding.txt.gz",
+        "resources/embs/simple-embedding.txt.zst",
+    ]
+    .iter()
+    {
+        let emb = W2VEmbeddingVectorsReader::new()
+            .read(file.parse().unwrap())
+            .unwrap();
+
+        assert_eq!(emb.dim(), 3);
+        assert_eq!(*emb.get_vector(0).unwrap(), [1.0, 2.0, 3.0]);
+        assert_eq!(*emb.get_vector(1).unwrap(), [3.0, 2.0, 1.0]);
+    }
+}
+
+#[test]
+fn test_reading_compressed_binary_file() {
+    let emb = W2VBinaryEmbeddingReader::new()
+        .read("resources/embs/simple-embedding.bin.bz2".into())
+        .unwrap();
+
+    assert_eq!(*emb.get_vector(1).unwrap(), [3.0, 2.0, 1.0]);
+}
+
+#[test]
+fn test_reading_from_memory() {
+    let emb = W2VEmbeddingVectorsReader::new()
+        .read_from("2 2\n0 1.0 2.0\n1 3.0 4.0\n".as_bytes())
+        .unwrap();
+
+    assert_eq!(emb.dim(), 2);
+    assert_eq!(*emb.get_vector(1).unwrap(), [3.0, 4.0]);
+
+    let mut binary = b"1 2\n7 ".to_vec();
+    for v in [0.5f32, -1.0].iter() {
+        binary.extend_from_slice(&v.to_le_bytes());
+    }
+    let emb = W2VBinaryEmbeddingReader::new()
+        .read_from(binary.as_slice())
+        .unwrap();
+
+    assert_eq!(*emb.get_vector(7).unwrap(), [0.5, -1.0]);
+}
+
+#[test]
+fn test_reading_from_memory_error() {
+    let err = W2VEmbeddingVectorsReader::new()
+        .read_from("2 2\n0 1.0 2.0\n1 3.0\n".as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
+    );
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.path(), None);
+}
+
+#[test]
+fn test_reading_npz_from_memory() {
+    let content = std::fs::read("resources/embs/int-keys.npz").unwrap();
+    let from_memory = NpzEmbeddingReader::new()
+        .read_from(content.as_slice())
+        .unwrap();
+    let from_file = NpzEmbeddingReader::new()
+        .read("resources/embs/int-keys.npz".into())
+        .unwrap();
+
+    assert_eq!(from_memory.dim(), from_file.dim());
+    for key in from_file.keys() {
+        assert_eq!(from_memory.get_vector(*key), from_file.get_vector(*key));
+    }
+}
+
+#[test]
+fn test_reading_string_keys() {
+    let emb = W2VEmbeddingVectorsReader::<String>::with_key_type()
+        .read_from("2 2\nalice 1.0 2.0\nbob 3.0 4.0\n".as_bytes())
+        .unwrap();
+
+    assert_eq!(*emb.get_vector("alice".to_string()).unwrap(), [1.0, 2.0]);
+    assert_eq!(*emb.get_vector("bob".to_string()).unwrap(), [3.0, 4.0]);
+
+    let emb = W2VBinaryEmbeddingReader::<String>::with_key_type()
+        .read("resources/embs/string-keys.bin".into())
+        .unwrap();
+
+    assert_eq!(*emb.get_vector("carol".to_string()).unwrap(), [0.25, 4.0]);
+}
+
+#[test]
+fn test_reading_custom_keys() {
+    let reader = W2VEmbeddingVectorsReader::with_key_parser(|key| {
+        let mut parts = key.splitn(2, ':');
+        let kind = parts.next().unwrap().to_string();
+        let id = parts
+            .next()
+            .and_then(|id| id.parse::<u32>().ok())
+            .ok_or_else(|| ReaderError::malformed("Couldn't parse typed key"))?;
+
+        Ok((kind, id))
+    });
+
+    let emb = reader
+        .read_from("2 2\nuser:1 1.0 2.0\nitem:1 3.0 4.0\n".as_bytes())
+        .unwrap();
+    assert_eq!(
+        *emb.get_vector(("item".to_string(), 1)).unwrap(),
+        [3.0, 4.0]
+    );
+
+    let err = reader
+        .read_from("1 2\nuser 1.0 2.0\n".as_bytes())
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse typed key".to_string())
+    );
+    assert_eq!(err.line(), Some(2));
+}
+
+#[test]
+fn test_reading_unparsable_keys() {
+    let err = W2VEmbeddingVectorsReader::<u8>::with_key_type()
+        .read_from("1 2\n300 1.0 2.0\n".as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse key".to_string())
+    );
+    assert_eq!(err.token(), Some("300"));
+}
+
+fn strict_reader() -> W2VEmbeddingVectorsReader {
+    let mut reader = W2VEmbeddingVectorsReader::new();
+    reader.set_strict(true);
+
+    reader
+}
+
+#[test]
+fn test_strict_accepts_valid_file() {
+    let emb = strict_reader()
+        .read("resources/embs/simple-embedding.txt".parse().unwrap())
+        .unwrap();
+
+    assert_eq!(emb.keys().len(), 2);
+}
+
+#[test]
+fn test_strict_row_count_mismatch() {
+    for (content, expected) in [
+        ("3 2\n0 1.0 2.0\n1 3.0 4.0\n", 3),
+        ("1 2\n0 1.0 2.0\n1 3.0 4.0\n", 1),
+    ]
+    .iter()
+    {
+        let err = strict_reader().read_from(content.as_bytes()).unwrap_err();
+
+        assert_eq!(
+            *err.kind(),
+            ErrorKind::RowCountMismatch {
+                expected: *expected,
+                found: 2,
+            }
+        );
+    }
+
+    // Without strict mode the header count is not checked
+    let emb = W2VEmbeddingVectorsReader::new()
+        .read_from("3 2\n0 1.0 2.0\n".as_bytes())
+        .unwrap();
+    assert_eq!(emb.keys().len(), 1);
+}
+
+#[test]
+fn test_strict_duplicate_key() {
+    let content = "2 2\n0 1.0 2.0\n0 3.0 4.0\n";
+    let err = strict_reader().read_from(content.as_bytes()).unwrap_err();
+
+    assert_eq!(*err.kind(), ErrorKind::DuplicateKey);
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.token(), Some("0"));
+
+    // The last vector wins otherwise
+    let emb = W2VEmbeddingVectorsReader::new()
+        .read_from(content.as_bytes())
+        .unwrap();
+    assert_eq!(*emb.get_vector(0).unwrap(), [3.0, 4.0]);
+}
+
+#[test]
+fn test_strict_non_finite_values() {
+    for value in ["NaN", "inf", "-inf"].iter() {
+        let content = format!("2 2\n0 1.0 2.0\n1 3.0 {}\n", value);
+        let err = strict_reader().read_from(content.as_bytes()).unwrap_err();
+
+        assert_eq!(*err.kind(), ErrorKind::NonFiniteValue);
+        assert_eq!(err.line(), Some(3));
+        assert_eq!(err.token(), Some(*value));
+    }
+}
+
+#[test]
+fn test_strict_lenient_skips_bad_rows() {
+    let file = tmp_file("strict.txt");
+    std::fs::write(&file, "3 2\n0 1.0 2.0\n0 3.0 4.0\n1 NaN 1.0\n").unwrap();
+
+    // The skipped rows count towards the 3 rows of the header
+    let (emb, report) = strict_reader().read_lenient(file.clone(), 2).unwrap();
+    std::fs::remove_file(file).unwrap();
+
+    assert_eq!(emb.keys().len(), 1);
+    assert_eq!(report.num_skipped(), 2);
+    assert_eq!(*report.skipped()[0].kind(), ErrorKind::DuplicateKey);
+    assert_eq!(*report.skipped()[1].kind(), ErrorKind::NonFiniteValue);
+}
@@ -0,0 +1,48 @@
+/// Unit tests for similarity metrics
+use crate::embedding::similarity::*;
+
+const EPS: f64 = 1e-12;
+
+#[test]
+fn test_metrics_between_vectors() {
+    let a = [1.0, 0.0];
+    let b = [3.0, 4.0];
+
+    assert!((Metric::Cosine.between(&a, &b) - 0.6).abs() < EPS);
+    assert!((Metric::Dot.between(&a, &b) - 3.0).abs() < EPS);
+    assert!((Metric::Euclidean.between(&a, &b) - 20f64.sqrt()).abs() < EPS);
+}
+
+#[test]
+fn test_cosine_of_zero_vector() {
+    assert_eq!(Metric::Cosine.between(&[0.0, 0.0], &[1.0, 2.0]), 0.0);
+}
+
+#[test]
+fn test_scores_match_pairwise_metrics() {
+    let matrix = arr2(&[[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0], [0.0, 0.0, 0.0]]);
+    let query = arr1(&[0.5, -2.0, 1.0]);
+
+    for metric in [Metric::Cosine, Metric::Dot, Metric::Euclidean].iter() {
+        let scores = scores(matrix.view(), query.view(), *metric);
+
+        for (row, score) in matrix.outer_iter().zip(scores.iter()) {
+            let expected = metric.between(row.as_slice().unwrap(), query.as_slice().unwrap());
+            assert!((score - expected).abs() < EPS);
+        }
+    }
+}
+
+#[test]
+fn test_top_k_order() {
+    let scores = [0.5, 2.0, -1.0, 2.0, f64::NAN, 1.0];
+
+    assert_eq!(top_k(&scores, 3, Metric::Dot), vec![1, 3, 5]);
+    assert_eq!(top_k(&scores, 3, Metric::Euclidean), vec![2, 0, 5]);
+    assert_eq!(top_k(&scores, 10, Metric::Dot), vec![1, 3, 5, 0, 2, 4]);
+    assert_eq!(
+        top_k(&scores, 10, Metric::Euclidean),
+        vec![2, 0, 5, 1, 3, 4]
+    );
+    assert!(top_k(&scores, 0, Metric::Cosine).is_empty());
+}
@@ -0,0 +1,250 @@
+/// Unit tests for embedding file writer
+use std::fs;
+
+use rand::prelude::*;
+
+use crate::embedding::model::{Embedding, EmbeddingKey};
+use crate::embedding::reader::*;
+use crate::embedding::writer::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::embedding::writer::writer_tests::utils::*;
+
+fn write_to_string<K, W>(writer: &W, emb: &Embedding<K>, name: &str) -> String
+where
+    K: EmbeddingKey,
+    W: EmbeddingWriter<K>,
+{
+    let file = tmp_file(&format!("{}.txt", name));
+
+    writer.write(emb, file.clone()).unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    content
+}
+
+#[test]
+fn test_writing_simple_embedding() {
+    let mut emb: Embedding<usize> = Embedding::new(3);
+    emb.add_vector(1, &[3.0, 2.0, 1.5]).unwrap();
+    emb.add_vector(0, &[1.0, 2.0, 3.0]).unwrap();
+
+    let content = write_to_string(&W2VEmbeddingVectorsWriter::new(), &emb, "emb-simple");
+
+    assert_eq!(content, "2 3\n0 1 2 3\n1 3 2 1.5\n");
+}
+
+#[test]
+fn test_writing_with_precision() {
+    let mut emb: Embedding<usize> = Embedding::new(2);
+    emb.add_vector(0, &[1.0, 1.0 / 3.0]).unwrap();
+
+    let mut writer = W2VEmbeddingVectorsWriter::new();
+    writer.set_precision(4);
+
+    let content = write_to_string(&writer, &emb, "emb-precision");
+
+    assert_eq!(content, "1 2\n0 1.0000 0.3333\n");
+}
+
+#[test]
+fn test_writing_textual_keys_ordered() {
+    let mut emb: Embedding<&str> = Embedding::new(1);
+    emb.add_vector("carol", &[3.0]).unwrap();
+    emb.add_vector("alice", &[1.0]).unwrap();
+    emb.add_vector("bob", &[2.0]).unwrap();
+
+    let content = write_to_string(&W2VEmbeddingVectorsWriter::new(), &emb, "emb-textual");
+
+    assert_eq!(content, "3 1\nalice 1\nbob 2\ncarol 3\n");
+}
+
+#[test]
+fn test_writing_empty_embedding() {
+    let emb: Embedding<usize> = Embedding::new(8);
+
+    let content = write_to_string(&W2VEmbeddingVectorsWriter::new(), &emb, "emb-empty");
+
+    assert_eq!(content, "0 8\n");
+}
+
+#[test]
+fn test_writing_to_missing_directory() {
+    let emb: Embedding<usize> = Embedding::new(8);
+
+    let res = W2VEmbeddingVectorsWriter::new().write(&emb, "/tmp/non-existing-dir/emb.txt".into());
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_round_trip() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut emb: Embedding<usize> = Embedding::new(16);
+
+    for key in 0..100 {
+        let vector: Vec<f64> = (0..16).map(|_| rng.gen::<f64>() - 0.5).collect();
+        emb.add_vector(key, &vector).unwrap();
+    }
+
+    let file = tmp_file("emb-round-trip.txt");
+    W2VEmbeddingVectorsWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+    let read = W2VEmbeddingVectorsReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(read.dim(), emb.dim());
+    assert_eq!(read.keys().len(), emb.keys().len());
+    for key in emb.keys() {
+        assert_eq!(read.get_vector(*key), emb.get_vector(*key));
+    }
+}
+
+#[test]
+fn test_deterministic_output() {
+    let mut emb: Embedding<usize> = Embedding::new(2);
+    for key in (0..50).rev() {
+        emb.add_vector(key, &[key as f64, 0.5]).unwrap();
+    }
+
+    let writer = W2VEmbeddingVectorsWriter::new();
+
+    assert_eq!(
+        write_to_string(&writer, &emb, "emb-deterministic-1"),
+        write_to_string(&writer, &emb, "emb-deterministic-2")
+    );
+}
+
+#[test]
+fn test_binary_writer_output() {
+    let mut emb: Embedding<usize> = Embedding::new(3);
+    emb.add_vector(1, &[3.0, 2.0, 1.0]).unwrap();
+    emb.add_vector(0, &[1.0, 2.0, 3.0]).unwrap();
+
+    let file = tmp_file("emb-binary-output.txt");
+    W2VBinaryEmbeddingWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+    let content = fs::read(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(
+        content,
+        fs::read("resources/embs/simple-embedding.bin").unwrap()
+    );
+}
+
+#[test]
+fn test_binary_round_trip_string_keys() {
+    let mut emb: Embedding<&str> = Embedding::new(4);
+    emb.add_vector("alice", &[1.0, 0.5, -0.25, 8.0]).unwrap();
+    emb.add_vector("bob", &[0.0, 0.0, 0.0, 0.0]).unwrap();
+
+    let file = tmp_file("emb-binary-round-trip.txt");
+    W2VBinaryEmbeddingWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+    let (read, vocab) = W2VBinaryEmbeddingReader::new()
+        .read_interned(file.clone())
+        .unwrap();
+    fs::remove_file(file).unwrap();
+
+    let decoded = vocab.decode_embedding(&read).unwrap();
+    assert_eq!(decoded.keys().len(), 2);
+    for key in emb.keys() {
+        assert_eq!(decoded.get_vector(*key), emb.get_vector(*key));
+    }
+}
+
+#[test]
+fn test_binary_round_trip_narrows_to_f32() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut emb: Embedding<usize> = Embedding::new(16);
+
+    for key in 0..100 {
+        let vector: Vec<f64> = (0..16).map(|_| rng.gen::<f64>() - 0.5).collect();
+        emb.add_vector(key, &vector).unwrap();
+    }
+
+    let file = tmp_file("emb-binary-round-trip-f32.txt");
+    W2VBinaryEmbeddingWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+    let read = W2VBinaryEmbeddingReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(read.keys().len(), 100);
+    for key in emb.keys() {
+        let expected: Vec<f64> = emb
+            .get_vector(*key)
+            .unwrap()
+            .iter()
+            .map(|v| *v as f32 as f64)
+            .collect();
+        assert_eq!(read.get_vector(*key).unwrap(), expected.as_slice());
+    }
+}
+
+#[test]
+fn test_npy_round_trip() {
+    let mut emb: Embedding<usize> = Embedding::new(3);
+    emb.add_vector(5, &[0.1, 0.2, 0.3]).unwrap();
+    emb.add_vector(2, &[-1.0, 1e-9, 7.0]).unwrap();
+
+    let file = tmp_file("emb-npy.txt");
+    let keys_file = tmp_file("emb-npy-keys.txt");
+    NpyEmbeddingWriter::new(keys_file.clone())
+        .write(&emb, file.clone())
+        .unwrap();
+    let keys = fs::read_to_string(&keys_file).unwrap();
+    let read = NpyEmbeddingReader::new(keys_file.clone())
+        .read(file.clone())
+        .unwrap();
+    fs::remove_file(file).unwrap();
+    fs::remove_file(keys_file).unwrap();
+
+    assert_eq!(keys, "2\n5\n");
+    assert_eq!(read.dim(), 3);
+    assert_eq!(read.keys().len(), 2);
+    for key in emb.keys() {
+        assert_eq!(read.get_vector(*key), emb.get_vector(*key));
+    }
+}
+
+#[test]
+fn test_npz_round_trip_string_keys() {
+    let mut emb: Embedding<&str> = Embedding::new(2);
+    emb.add_vector("alice", &[1.0, 0.5]).unwrap();
+    emb.add_vector("bob", &[-0.25, 3.0]).unwrap();
+
+    let file = tmp_file("emb-npz.txt");
+    NpzEmbeddingWriter::new().write(&emb, file.clone()).unwrap();
+    let (read, vocab) = NpzEmbeddingReader::new()
+        .read_interned(file.clone())
+        .unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(read.dim(), 2);
+    let decoded = vocab.decode_embedding(&read).unwrap();
+    for key in emb.keys() {
+        assert_eq!(decoded.get_vector(*key), emb.get_vector(*key));
+    }
+}
+
+#[test]
+fn test_npz_round_trip_empty_embedding() {
+    let emb: Embedding<usize> = Embedding::new(4);
+
+    let file = tmp_file("emb-npz-empty.txt");
+    NpzEmbeddingWriter::new().write(&emb, file.clone()).unwrap();
+    let read = NpzEmbeddingReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(read.dim(), 4);
+    assert!(read.keys().is_empty());
+}
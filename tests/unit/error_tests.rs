@@ -0,0 +1,106 @@
+/// Unit tests for reader errors
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+use crate::embedding::model::EmbeddingError;
+use crate::error::*;
+use crate::network::model::NetworkError;
+
+#[test]
+fn test_not_found_io_error() {
+    let err = ReaderError::from(io::Error::from(io::ErrorKind::NotFound));
+
+    assert_eq!(*err.kind(), ErrorKind::FileNotFound);
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn test_other_io_error_does_not_panic() {
+    let err = ReaderError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::Io(io::Error::from(io::ErrorKind::PermissionDenied))
+    );
+    assert!(err.source().is_some(), "IO error should be the source");
+}
+
+#[test]
+fn test_parse_token() {
+    let value: Result<usize, ReaderError> = parse_token("12", "int");
+    assert_eq!(value.unwrap(), 12);
+
+    let err = parse_token::<usize>("1x", "int").unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string())
+    );
+    assert_eq!(err.token(), Some("1x"));
+}
+
+#[test]
+fn test_location_set_once() {
+    let err = ReaderError::malformed("Not enough elements")
+        .at_line(3)
+        .at_line(7)
+        .with_path(Path::new("a.txt"))
+        .with_path(Path::new("b.txt"));
+
+    assert_eq!(err.line(), Some(3), "Innermost line should be kept");
+    assert_eq!(err.path(), Some(Path::new("a.txt")));
+}
+
+#[test]
+fn test_display() {
+    let err = ReaderError::malformed("Couldn't parse float")
+        .with_token("1.0x")
+        .at_line(2)
+        .with_path(Path::new("net.txt"));
+
+    assert_eq!(
+        err.to_string(),
+        "net.txt:2: Couldn't parse float (offending token: \"1.0x\")"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::FileNotFound).to_string(),
+        "File not found"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::VersionMismatch {
+            found: 3,
+            supported: 1
+        })
+        .to_string(),
+        "Unsupported format version 3 (up to 1 is supported)"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::RowCountMismatch {
+            expected: 3,
+            found: 2
+        })
+        .to_string(),
+        "Header announces 3 rows but 2 were found"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::InvalidEmbedding(
+            EmbeddingError::WrongEmbeddingDimension("Expected: 3 Got: 2".to_string())
+        ))
+        .to_string(),
+        "Invalid embedding: Wrong embedding dimension (Expected: 3 Got: 2)"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::InvalidNetwork(NetworkError::SelfLoop(
+            "Self-loop (1, 1) is not allowed".to_string()
+        )))
+        .to_string(),
+        "Invalid network: Self-loop (1, 1) is not allowed"
+    );
+    assert_eq!(
+        ReaderError::new(ErrorKind::InvalidNetwork(
+            NetworkError::WrongFeatureDimension("Expected: 3 Got: 2".to_string())
+        ))
+        .to_string(),
+        "Invalid network: Wrong feature dimension (Expected: 3 Got: 2)"
+    );
+}
@@ -0,0 +1,90 @@
+/// Unit tests for GML network reader
+use crate::network::gml::*;
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::gml::gml_tests::utils::*;
+
+#[test]
+fn test_reading_simple_gml() {
+    let net = GmlReader::new()
+        .read("resources/nets/simple.gml".into())
+        .unwrap();
+
+    assert!(net.is_directed());
+    assert_eq!(net.node_label(0), Some("alice"));
+    assert_eq!(net.node_label(2), Some("carol"));
+    assert_eq!(net.node_label(3), None);
+
+    check_network(
+        net,
+        4,
+        3,
+        vec![0, 1, 2, 3],
+        vec![(0, 1, vec![&16.0]), (1, 2, vec![&18.0]), (2, 0, vec![&1.0])],
+    );
+}
+
+#[test]
+fn test_chosen_weight_attribute() {
+    let mut reader = GmlReader::new();
+    reader.set_weight_attribute("value");
+
+    let net = reader.read("resources/nets/simple.gml".into()).unwrap();
+
+    assert_eq!(net.edge_data(0, 1).unwrap(), vec![&3.0]);
+    assert_eq!(net.edge_data(1, 2).unwrap(), vec![&1.0]);
+}
+
+#[test]
+fn test_unmatched_bracket() {
+    let err = GmlReader::new()
+        .read("resources/nets/malformed-unmatched.gml".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unexpected end of file".to_string())
+    );
+}
+
+#[test]
+fn test_deeply_nested_lists() {
+    let input = format!("graph {}{}", "[ a ".repeat(100_000), "]".repeat(100_000));
+
+    let err = GmlReader::new().read_from(input.as_bytes()).unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Lists nested too deeply".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_missing_target() {
+    let err = GmlReader::new()
+        .read("resources/nets/malformed-missing-target.gml".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Missing target".to_string())
+    );
+    assert_eq!(err.line(), Some(3));
+}
+
+#[test]
+fn test_edge_list_is_not_gml() {
+    let err = GmlReader::new()
+        .read("resources/nets/simple-net.txt".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Missing value".to_string())
+    );
+}
@@ -0,0 +1,121 @@
+/// Unit tests for GraphML network reader
+use crate::network::graphml::*;
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::graphml::graphml_tests::utils::*;
+
+#[test]
+fn test_reading_simple_graphml() {
+    let net = GraphMLReader::new()
+        .read("resources/nets/simple.graphml".into())
+        .unwrap();
+
+    assert!(net.is_directed());
+    check_network(
+        net,
+        4,
+        4,
+        vec![0, 1, 2, 3],
+        vec![
+            (0, 1, vec![&16.0]),
+            (1, 2, vec![&18.0]),
+            (2, 0, vec![&1.5]),
+            (0, 2, vec![&1.5]),
+        ],
+    );
+}
+
+#[test]
+fn test_chosen_weight_attribute() {
+    let mut reader = GraphMLReader::new();
+    reader.set_weight_attribute("capacity");
+
+    let net = reader.read("resources/nets/simple.graphml".into()).unwrap();
+
+    assert_eq!(net.edge_data(0, 1).unwrap(), vec![&2.0]);
+    assert_eq!(
+        net.edge_data(1, 2).unwrap(),
+        vec![&1.0],
+        "No data nor default"
+    );
+}
+
+#[test]
+fn test_reading_string_ids() {
+    let (net, vocab) = GraphMLReader::new()
+        .read_interned("resources/nets/string-ids.graphml".into())
+        .unwrap();
+
+    assert!(!net.is_directed());
+    assert_eq!(net.num_nodes(), 3);
+    assert_eq!(net.num_edges(), 2);
+
+    let bob = vocab.id("bob").unwrap();
+    let carol = vocab.id("carol").unwrap();
+    assert_eq!(net.edge_data(carol, bob).unwrap(), vec![&1.0]);
+}
+
+#[test]
+fn test_non_integer_ids() {
+    let err = GraphMLReader::new()
+        .read("resources/nets/string-ids.graphml".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string())
+    );
+    assert_eq!(err.line(), Some(4));
+    assert_eq!(err.token(), Some("alice"));
+}
+
+#[test]
+fn test_malformed_weight() {
+    let err = GraphMLReader::new()
+        .read("resources/nets/malformed-weight.graphml".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse float".to_string())
+    );
+    assert_eq!(err.line(), Some(5));
+    assert_eq!(err.token(), Some("heavy"));
+}
+
+#[test]
+fn test_invalid_xml() {
+    let err = GraphMLReader::new()
+        .read("resources/nets/invalid-xml.graphml".into())
+        .unwrap_err();
+
+    match err.kind() {
+        ErrorKind::MalformedFileFormat(msg) => assert!(msg.starts_with("Invalid XML")),
+        kind => panic!("Unexpected error: {:?}", kind),
+    }
+    assert_eq!(err.line(), Some(5));
+    assert!(err.path().is_some());
+}
+
+#[test]
+fn test_reading_non_existing_graphml() {
+    let err = GraphMLReader::new()
+        .read("/tmp/non-existing-file.graphml".into())
+        .unwrap_err();
+
+    assert_eq!(*err.kind(), ErrorKind::FileNotFound);
+}
+
+#[test]
+fn test_reading_compressed_graphml() {
+    let net = GraphMLReader::new()
+        .read("resources/nets/simple.graphml.gz".into())
+        .unwrap();
+
+    assert_eq!(net.num_nodes(), 4);
+    assert_eq!(net.num_edges(), 4);
+}
@@ -0,0 +1,184 @@
+/// Unit tests for METIS graph reader and writer
+use std::fs;
+use std::io;
+
+use crate::network::metis::*;
+use crate::network::model::Network;
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::metis::metis_tests::utils::*;
+
+#[test]
+fn test_reading_edge_weights() {
+    let net = MetisReader::new()
+        .read("resources/nets/simple.metis".into())
+        .unwrap();
+
+    assert!(!net.is_directed());
+    check_network(
+        net,
+        4,
+        4,
+        vec![0, 1, 2, 3],
+        vec![
+            (0, 1, vec![&3.0]),
+            (0, 2, vec![&1.0]),
+            (1, 2, vec![&4.0]),
+            (1, 3, vec![&2.0]),
+            (3, 1, vec![&2.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_reading_vertex_weights() {
+    let net = MetisReader::new()
+        .read("resources/nets/vertex-weights.metis".into())
+        .unwrap();
+
+    check_network(
+        net,
+        3,
+        2,
+        vec![0, 1, 2],
+        vec![(0, 1, vec![&7.0]), (1, 2, vec![&1.0])],
+    );
+}
+
+#[test]
+fn test_wrong_number_of_edges() {
+    let err = MetisReader::new()
+        .read("resources/nets/wrong-edges.metis".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Expected 3 edges, got 2".to_string())
+    );
+}
+
+#[test]
+fn test_wrong_number_of_vertices() {
+    let err = MetisReader::new()
+        .read("resources/nets/asymmetric.metis".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Expected 3 vertices, got 2".to_string())
+    );
+}
+
+#[test]
+fn test_more_vertices_than_lines() {
+    let err = MetisReader::new()
+        .read_from("100000000000 0\n\n".as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Expected 100000000000 vertices, got 1".to_string())
+    );
+}
+
+#[test]
+fn test_asymmetric_adjacency() {
+    for (content, message, line, token) in [
+        ("2 1\n1 2\n1\n", "Self-loops are not allowed", 2, Some("1")),
+        (
+            "3 1\n\n1\n\n",
+            "Vertex 1 doesn't list vertex 2",
+            3,
+            Some("1"),
+        ),
+        ("3 1\n2\n\n\n", "Vertex 2 doesn't list vertex 1", 2, None),
+        (
+            "2 1 001\n2 5\n1 6\n",
+            "Vertex 1 lists this edge with weight 5",
+            3,
+            Some("6"),
+        ),
+    ]
+    .iter()
+    {
+        let err = MetisReader::new()
+            .read_from(content.as_bytes())
+            .unwrap_err();
+
+        assert_eq!(
+            *err.kind(),
+            ErrorKind::MalformedFileFormat(message.to_string())
+        );
+        assert_eq!(err.line(), Some(*line));
+        assert_eq!(err.token(), *token);
+    }
+}
+
+#[test]
+fn test_malformed_header() {
+    let err = MetisReader::new()
+        .read("resources/nets/simple-net.txt".into())
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_round_trip() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.add_edge(0, 1, 3.0).unwrap();
+    net.add_edge(2, 0, 1.0).unwrap();
+    net.add_edge(1, 3, 2.0).unwrap();
+    net.add_node(5);
+
+    let file = tmp_file("metis-round-trip.metis");
+    MetisWriter::new().write(&net, file.clone()).unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    let read = MetisReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(content, "6 3 001\n2 3 3 1\n1 3 4 2\n1 1\n2 2\n\n\n");
+    check_network(
+        read,
+        6,
+        3,
+        vec![0, 1, 2, 3, 4, 5],
+        vec![(0, 1, vec![&3.0]), (0, 2, vec![&1.0]), (1, 3, vec![&2.0])],
+    );
+}
+
+#[test]
+fn test_writing_unweighted() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.add_edge(0, 1, 0.5).unwrap();
+
+    let mut writer = MetisWriter::new();
+    writer.set_write_weights(false);
+
+    let file = tmp_file("metis-unweighted.metis");
+    writer.write(&net, file.clone()).unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(content, "2 1\n2\n1\n");
+}
+
+#[test]
+fn test_writing_invalid_networks() {
+    let file = tmp_file("metis-invalid.metis");
+
+    let directed: Network<usize, f64> = Network::new(true);
+    let mut self_loop: Network<usize, f64> = Network::new(false);
+    self_loop.add_edge(0, 0, 1.0).unwrap();
+    let mut fractional: Network<usize, f64> = Network::new(false);
+    fractional.add_edge(0, 1, 0.5).unwrap();
+
+    for net in [directed, self_loop, fractional].iter() {
+        let err = MetisWriter::new().write(net, file.clone()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,192 @@
+/// Unit tests for node centrality measures
+use std::collections::HashMap;
+
+use crate::network::metrics::*;
+use crate::network::model::Network;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-4,
+        "Expected: {} Got: {}",
+        expected,
+        actual
+    );
+}
+
+fn triangle() -> Network<&'static str, f64> {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+    net.add_edge("C", "A", 1.0).unwrap();
+
+    net
+}
+
+fn path() -> Network<&'static str, f64> {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+
+    net
+}
+
+#[test]
+fn test_pagerank_empty_network() {
+    let net: Network<&str, f64> = Network::new(true);
+
+    assert!(pagerank(&net, 0.85, None).unwrap().is_empty());
+}
+
+#[test]
+fn test_pagerank_invalid_damping() {
+    let net = triangle();
+
+    assert_eq!(
+        pagerank(&net, 1.5, None).unwrap_err(),
+        MetricsError::InvalidParameter("Damping factor should be in [0, 1). Got: 1.5".to_string())
+    );
+}
+
+#[test]
+fn test_pagerank_symmetric() {
+    let net = triangle();
+
+    let ranks = pagerank(&net, 0.85, None).unwrap();
+
+    for node in ["A", "B", "C"].iter() {
+        assert_close(ranks[node], 1.0 / 3.0);
+    }
+}
+
+#[test]
+fn test_pagerank_weighted() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 3.0).unwrap();
+    net.add_edge("A", "C", 1.0).unwrap();
+
+    let ranks = pagerank(&net, 0.85, None).unwrap();
+
+    assert_close(ranks.values().sum(), 1.0);
+    assert!(ranks["B"] > ranks["C"], "B gets more weight from A than C");
+    assert!(ranks["C"] > ranks["A"], "Nothing points to A");
+}
+
+#[test]
+fn test_pagerank_personalized() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_node("C");
+
+    let mut personalization = HashMap::new();
+    personalization.insert("A", 1.0);
+
+    let ranks = pagerank(&net, 0.85, Some(&personalization)).unwrap();
+
+    assert_close(ranks.values().sum(), 1.0);
+    assert_close(ranks["C"], 0.0);
+}
+
+#[test]
+fn test_pagerank_invalid_personalization() {
+    let net = triangle();
+    let personalization: HashMap<&str, f64> = HashMap::new();
+
+    assert_eq!(
+        pagerank(&net, 0.85, Some(&personalization)).unwrap_err(),
+        MetricsError::InvalidParameter(
+            "Personalization should be non-negative with a positive sum".to_string()
+        )
+    );
+}
+
+#[test]
+fn test_betweenness_exact_path() {
+    let net = path();
+
+    let centrality = approx_betweenness(&net, 3, 0);
+
+    assert_close(centrality["A"], 0.0);
+    assert_close(centrality["B"], 1.0);
+    assert_close(centrality["C"], 0.0);
+}
+
+#[test]
+fn test_betweenness_directed() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+
+    let centrality = approx_betweenness(&net, 3, 0);
+
+    assert_close(centrality["B"], 0.5);
+}
+
+#[test]
+fn test_betweenness_sampled_is_deterministic() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    for i in 0..20 {
+        net.add_edge(i, i + 1, 1.0).unwrap();
+    }
+
+    let first = approx_betweenness(&net, 5, 42);
+    let second = approx_betweenness(&net, 5, 42);
+
+    assert_eq!(first, second, "Same seed should give the same estimate");
+    assert_eq!(first.len(), 21);
+}
+
+#[test]
+fn test_closeness_path() {
+    let net = path();
+
+    let centrality = closeness(&net);
+
+    assert_close(centrality["A"], 2.0 / 3.0);
+    assert_close(centrality["B"], 1.0);
+    assert_close(centrality["C"], 2.0 / 3.0);
+}
+
+#[test]
+fn test_closeness_directed() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_node("C");
+
+    let centrality = closeness(&net);
+
+    assert_close(centrality["A"], 0.0);
+    assert_close(centrality["B"], 0.5);
+    assert_close(centrality["C"], 0.0);
+}
+
+#[test]
+fn test_eigenvector_triangle() {
+    let net = triangle();
+
+    let centrality = eigenvector(&net).unwrap();
+
+    for node in ["A", "B", "C"].iter() {
+        assert_close(centrality[node], 1.0 / 3.0_f64.sqrt());
+    }
+}
+
+#[test]
+fn test_eigenvector_star() {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "C", 1.0).unwrap();
+    net.add_edge("A", "D", 1.0).unwrap();
+
+    let centrality = eigenvector(&net).unwrap();
+
+    assert!(centrality["A"] > centrality["B"]);
+    assert_close(centrality["B"], centrality["C"]);
+    assert_close(centrality["C"], centrality["D"]);
+}
@@ -1,6 +1,9 @@
 /// Unit tests for network data structure
+use ndarray::prelude::*;
+
 use crate::network::model::*;
 
+#[allow(clippy::duplicate_mod)]
 #[path = "../utils.rs"]
 mod utils;
 
@@ -39,7 +42,7 @@ fn test_edge_inserted_directed() {
     net.add_node(0);
     net.add_node(1);
 
-    net.add_edge(0, 1, 10.0);
+    net.add_edge(0, 1, 10.0).unwrap();
 
     check_network(net, 2, 1, vec![0, 1], vec![(0, 1, vec![&10.0])]);
 }
@@ -51,7 +54,7 @@ fn test_edge_inserted_undirected() {
     net.add_node(0);
     net.add_node(1);
 
-    net.add_edge(0, 1, 10.0);
+    net.add_edge(0, 1, 10.0).unwrap();
 
     check_network(
         net,
@@ -66,7 +69,7 @@ fn test_edge_inserted_undirected() {
 fn test_edge_inserted_without_adding_nodes_directed() {
     let mut net: Network<usize, f64> = Network::new(true);
 
-    net.add_edge(0, 1, 10.0);
+    net.add_edge(0, 1, 10.0).unwrap();
 
     check_network(net, 2, 1, vec![0, 1], vec![(0, 1, vec![&10.0])]);
 }
@@ -75,7 +78,7 @@ fn test_edge_inserted_without_adding_nodes_directed() {
 fn test_edge_inserted_without_adding_nodes_undirected() {
     let mut net: Network<usize, f64> = Network::new(false);
 
-    net.add_edge(0, 1, 10.0);
+    net.add_edge(0, 1, 10.0).unwrap();
 
     check_network(
         net,
@@ -100,7 +103,7 @@ fn test_edge_not_present() {
 fn test_edge_present_directed() {
     let mut net: Network<&str, usize> = Network::new(true);
 
-    net.add_edge("A", "B", 1);
+    net.add_edge("A", "B", 1).unwrap();
 
     assert!(
         net.edge_data("A", "B").is_some(),
@@ -117,7 +120,7 @@ fn test_edge_present_directed() {
 fn test_edge_present_undirected() {
     let mut net: Network<&str, usize> = Network::new(false);
 
-    net.add_edge("A", "B", 1);
+    net.add_edge("A", "B", 1).unwrap();
 
     assert!(
         net.edge_data("A", "B").is_some(),
@@ -160,8 +163,8 @@ fn test_neighbours_found_directed() {
     net.add_node("B");
     net.add_node("C");
 
-    net.add_edge("A", "B", 1.0);
-    net.add_edge("A", "C", 2.0);
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "C", 2.0).unwrap();
 
     let neighbours: Vec<&&str> = net.neighbours_of("A").unwrap();
 
@@ -178,8 +181,8 @@ fn test_neighbours_found_undirected() {
     net.add_node("B");
     net.add_node("C");
 
-    net.add_edge("A", "B", 1.0);
-    net.add_edge("A", "C", 2.0);
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "C", 2.0).unwrap();
 
     let neighbours: Vec<&&str> = net.neighbours_of("A").unwrap();
 
@@ -195,7 +198,7 @@ fn test_no_neighbours_dst_node_directed() {
     net.add_node("A");
     net.add_node("B");
 
-    net.add_edge("A", "B", 1.0);
+    net.add_edge("A", "B", 1.0).unwrap();
 
     assert!(
         net.neighbours_of("B").is_none(),
@@ -210,10 +213,191 @@ fn test_neighbours_found_dst_node_undirected() {
     net.add_node("A");
     net.add_node("B");
 
-    net.add_edge("A", "B", 1.0);
+    net.add_edge("A", "B", 1.0).unwrap();
 
     let neighbours: Vec<&&str> = net.neighbours_of("B").unwrap();
 
     assert_eq!(neighbours.len(), 1, "There should be one neighbour: A.");
     assert!(neighbours.contains(&&"A"), "Should contain A node.");
 }
+
+#[test]
+fn test_self_loop_inserted_once_undirected() {
+    let mut net: Network<usize, f64> = Network::new(false);
+
+    net.add_edge(0, 0, 1.0).unwrap();
+    net.add_edge(0, 1, 2.0).unwrap();
+
+    check_network(
+        net,
+        2,
+        2,
+        vec![0, 1],
+        vec![(0, 0, vec![&1.0]), (0, 1, vec![&2.0]), (1, 0, vec![&2.0])],
+    );
+}
+
+#[test]
+fn test_multi_edges_kept_by_default() {
+    let mut net: Network<usize, f64> = Network::new(true);
+
+    net.add_edge(0, 1, 1.0).unwrap();
+    net.add_edge(0, 1, 2.0).unwrap();
+
+    check_network(net, 2, 2, vec![0, 1], vec![(0, 1, vec![&2.0, &1.0])]);
+}
+
+#[test]
+fn test_multi_edges_merged_undirected() {
+    let policies: Vec<(MultiEdgePolicy<f64>, f64)> = vec![
+        (MultiEdgePolicy::sum(), 6.0),
+        (MultiEdgePolicy::max(), 3.0),
+        (MultiEdgePolicy::min(), 1.0),
+        (MultiEdgePolicy::last(), 2.0),
+    ];
+
+    for (policy, expected) in policies {
+        let mut net: Network<usize, f64> = Network::new(false);
+        net.set_multi_edge_policy(policy);
+
+        net.add_edge(0, 1, 3.0).unwrap();
+        net.add_edge(0, 1, 1.0).unwrap();
+        net.add_edge(1, 0, 2.0).unwrap();
+
+        check_network(
+            net,
+            2,
+            1,
+            vec![0, 1],
+            vec![(0, 1, vec![&expected]), (1, 0, vec![&expected])],
+        );
+    }
+}
+
+#[test]
+fn test_multi_edges_rejected() {
+    let mut net: Network<usize, f64> = Network::new(true);
+    net.set_multi_edge_policy(MultiEdgePolicy::Reject);
+
+    net.add_edge(0, 1, 1.0).unwrap();
+    let res = net.add_edge(0, 1, 2.0);
+
+    assert_eq!(
+        res.unwrap_err(),
+        NetworkError::DuplicateEdge("Edge (0, 1) already exists".to_string())
+    );
+    check_network(net, 2, 1, vec![0, 1], vec![(0, 1, vec![&1.0])]);
+}
+
+#[test]
+fn test_self_loops_dropped() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.set_self_loop_policy(SelfLoopPolicy::Drop);
+
+    let res = net.add_edge(0, 0, 1.0);
+
+    assert_eq!(res, Ok(None), "Self-loop should be dropped");
+    check_network(net, 1, 0, vec![0], vec![]);
+}
+
+#[test]
+fn test_self_loops_rejected() {
+    let mut net: Network<usize, f64> = Network::new(true);
+    net.set_self_loop_policy(SelfLoopPolicy::Error);
+
+    let res = net.add_edge(0, 0, 1.0);
+
+    assert_eq!(
+        res.unwrap_err(),
+        NetworkError::SelfLoop("Self-loop (0, 0) is not allowed".to_string())
+    );
+    assert!(net.edge_data(0, 0).is_none());
+}
+
+#[test]
+fn test_no_node_features_by_default() {
+    let mut net: Network<usize, f64> = Network::new(false);
+
+    net.add_node(0);
+
+    assert!(net.node_features(0).is_none());
+    assert!(net.node_label(0).is_none());
+    assert!(net.feature_dim().is_none());
+}
+
+#[test]
+fn test_node_features_dense_and_sparse() {
+    let mut net: Network<usize, f64> = Network::new(false);
+
+    net.set_node_features(0, NodeFeatures::Dense(arr1(&[1.0, 0.0, 2.0])))
+        .unwrap();
+    net.set_node_features(
+        1,
+        NodeFeatures::Sparse {
+            dim: 3,
+            entries: vec![(1, 5.0)],
+        },
+    )
+    .unwrap();
+
+    assert_eq!(net.feature_dim(), Some(3));
+    assert_eq!(net.num_nodes(), 2, "Nodes should be added with features");
+    assert_eq!(
+        net.node_features(0).unwrap().to_dense(),
+        arr1(&[1.0, 0.0, 2.0])
+    );
+    assert_eq!(
+        net.node_features(1).unwrap().to_dense(),
+        arr1(&[0.0, 5.0, 0.0])
+    );
+}
+
+#[test]
+fn test_node_features_wrong_dim() {
+    let mut net: Network<usize, f64> = Network::new(false);
+
+    net.set_node_features(0, NodeFeatures::Dense(arr1(&[1.0, 2.0])))
+        .unwrap();
+    let res = net.set_node_features(1, NodeFeatures::Dense(arr1(&[1.0, 2.0, 3.0])));
+
+    assert_eq!(
+        res.unwrap_err(),
+        NetworkError::WrongFeatureDimension("Expected: 2 Got: 3".to_string())
+    );
+    assert!(net.node_features(1).is_none());
+}
+
+#[test]
+fn test_node_features_sparse_index_out_of_range() {
+    let mut net: Network<usize, f64> = Network::new(false);
+
+    let res = net.set_node_features(
+        0,
+        NodeFeatures::Sparse {
+            dim: 2,
+            entries: vec![(2, 1.0)],
+        },
+    );
+
+    assert_eq!(
+        res.unwrap_err(),
+        NetworkError::WrongFeatureDimension(
+            "Sparse index 2 out of range for dimension 2".to_string()
+        )
+    );
+    assert!(net.feature_dim().is_none());
+}
+
+#[test]
+fn test_node_labels() {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.set_node_label("A", "red");
+    net.set_node_label("C", "blue");
+
+    assert_eq!(net.node_label("A"), Some("red"));
+    assert_eq!(net.node_label("B"), None);
+    assert_eq!(net.node_label("C"), Some("blue"));
+    assert_eq!(net.num_nodes(), 3);
+}
@@ -0,0 +1,184 @@
+/// Unit tests for Matrix Market network reader and writer
+use std::fs;
+
+use crate::network::model::Network;
+use crate::network::mtx::*;
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::mtx::mtx_tests::utils::*;
+
+#[test]
+fn test_reading_general_real() {
+    let net = MatrixMarketReader::new()
+        .read("resources/nets/general-real.mtx".into())
+        .unwrap();
+
+    assert!(net.is_directed());
+    check_network(
+        net,
+        4,
+        3,
+        vec![0, 1, 2, 3],
+        vec![
+            (0, 1, vec![&16.0]),
+            (1, 2, vec![&18.5]),
+            (2, 0, vec![&20.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_reading_symmetric_pattern() {
+    let net = MatrixMarketReader::new()
+        .read("resources/nets/symmetric-pattern.mtx".into())
+        .unwrap();
+
+    assert!(!net.is_directed());
+    check_network(
+        net,
+        3,
+        3,
+        vec![0, 1, 2],
+        vec![
+            (0, 1, vec![&1.0]),
+            (1, 0, vec![&1.0]),
+            (1, 2, vec![&1.0]),
+            (2, 2, vec![&1.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_array_format_unsupported() {
+    let err = MatrixMarketReader::new()
+        .read("resources/nets/array.mtx".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Only coordinate matrices are supported".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_index_out_of_range() {
+    let err = MatrixMarketReader::new()
+        .read("resources/nets/wrong-entries.mtx".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Index out of range".to_string())
+    );
+    assert_eq!(err.line(), Some(4));
+    assert_eq!(err.token(), Some("4"));
+}
+
+#[test]
+fn test_matrix_size_limit() {
+    let header = "%%MatrixMarket matrix coordinate pattern general\n";
+
+    let content = format!("{}100000000000 1 0\n", header);
+    let err = MatrixMarketReader::new()
+        .read_from(content.as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat(
+            "Matrix size exceeds the limit of 16777216 nodes".to_string()
+        )
+    );
+    assert_eq!(err.line(), Some(2));
+
+    let content = format!("{}3 3 1\n1 3\n", header);
+    let mut reader = MatrixMarketReader::new();
+    reader.set_max_nodes(2);
+    assert!(reader.read_from(content.as_bytes()).is_err());
+
+    reader.set_max_nodes(3);
+    assert_eq!(reader.read_from(content.as_bytes()).unwrap().num_nodes(), 3);
+}
+
+#[test]
+fn test_edge_list_is_not_mtx() {
+    let err = MatrixMarketReader::new()
+        .read("resources/nets/simple-net.txt".into())
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(1));
+    assert_eq!(err.token(), Some("0,1,16.0"));
+}
+
+#[test]
+fn test_writer_output() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.add_edge(0, 2, 1.5).unwrap();
+    net.add_edge(1, 1, 2.0).unwrap();
+
+    let file = tmp_file("mtx-output.mtx");
+    MatrixMarketWriter::new().write(&net, file.clone()).unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(
+        content,
+        "%%MatrixMarket matrix coordinate real symmetric\n3 3 2\n3 1 1.5\n2 2 2\n"
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    for directed in [true, false].iter() {
+        let mut expected: Network<usize, f64> = Network::new(*directed);
+        expected.add_node(3);
+        expected.add_edge(0, 1, 16.0).unwrap();
+        expected.add_edge(1, 2, 18.5).unwrap();
+        expected.add_edge(2, 0, 20.0).unwrap();
+        expected.add_edge(2, 2, 1.0).unwrap();
+
+        let file = tmp_file("mtx-round-trip.mtx");
+        MatrixMarketWriter::new()
+            .write(&expected, file.clone())
+            .unwrap();
+        let read = MatrixMarketReader::new().read(file.clone()).unwrap();
+        fs::remove_file(file).unwrap();
+
+        assert_eq!(read.is_directed(), *directed);
+        assert_eq!(read.num_nodes(), expected.num_nodes());
+        assert_eq!(read.num_edges(), expected.num_edges());
+        for (from, to) in expected.edges() {
+            assert_eq!(read.edge_data(*from, *to), expected.edge_data(*from, *to));
+        }
+    }
+}
+
+#[test]
+fn test_round_trip_pattern() {
+    let mut net: Network<usize, f64> = Network::new(true);
+    net.add_edge(0, 1, 1.0).unwrap();
+    net.add_edge(1, 0, 1.0).unwrap();
+
+    let mut writer = MatrixMarketWriter::new();
+    writer.set_write_weights(false);
+
+    let file = tmp_file("mtx-round-trip-pattern.mtx");
+    writer.write(&net, file.clone()).unwrap();
+    let content = fs::read_to_string(&file).unwrap();
+    let read = MatrixMarketReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert!(content.starts_with("%%MatrixMarket matrix coordinate pattern general\n"));
+    check_network(
+        read,
+        2,
+        2,
+        vec![0, 1],
+        vec![(0, 1, vec![&1.0]), (1, 0, vec![&1.0])],
+    );
+}
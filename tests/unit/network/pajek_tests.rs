@@ -0,0 +1,126 @@
+/// Unit tests for Pajek network reader
+use crate::network::pajek::*;
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::pajek::pajek_tests::utils::*;
+
+#[test]
+fn test_reading_simple_pajek() {
+    let net = PajekReader::new()
+        .read("resources/nets/simple.net".into())
+        .unwrap();
+
+    assert!(net.is_directed());
+    assert_eq!(net.node_label(1), Some("alice"));
+    assert_eq!(net.node_label(2), Some("bob smith"));
+    assert_eq!(net.node_label(4), None);
+
+    check_network(
+        net,
+        4,
+        6,
+        vec![1, 2, 3, 4],
+        vec![
+            (1, 2, vec![&16.0]),
+            (2, 3, vec![&18.0]),
+            (3, 1, vec![&20.0]),
+            (1, 3, vec![&20.0]),
+            (4, 1, vec![&1.0]),
+            (4, 2, vec![&1.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_reading_undirected_pajek() {
+    let net = PajekReader::new()
+        .read("resources/nets/undirected.net".into())
+        .unwrap();
+
+    assert!(!net.is_directed());
+    check_network(
+        net,
+        3,
+        2,
+        vec![1, 2, 3],
+        vec![(2, 1, vec![&1.0]), (3, 2, vec![&2.5])],
+    );
+}
+
+#[test]
+fn test_chosen_weight_column() {
+    let input = "*Vertices 2\n*Arcs\n1 2 16.0 0.5\n2 1 18.0\n";
+
+    let mut reader = PajekReader::new();
+    reader.set_weight_column(Some(3));
+    let net = reader.read_from(input.as_bytes()).unwrap();
+
+    assert_eq!(net.edge_data(1, 2).unwrap(), vec![&0.5]);
+    assert_eq!(net.edge_data(2, 1).unwrap(), vec![&1.0]);
+
+    reader.set_weight_column(None);
+    let net = reader.read_from(input.as_bytes()).unwrap();
+
+    assert_eq!(net.edge_data(1, 2).unwrap(), vec![&1.0]);
+    assert_eq!(net.edge_data(2, 1).unwrap(), vec![&1.0]);
+}
+
+#[test]
+fn test_vertex_limit() {
+    let err = PajekReader::new()
+        .read_from("% huge\n*Vertices 100000000000\n".as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat(
+            "Number of vertices exceeds the limit of 16777216 nodes".to_string()
+        )
+    );
+    assert_eq!(err.line(), Some(2));
+    assert_eq!(err.token(), Some("100000000000"));
+
+    let mut reader = PajekReader::new();
+    reader.set_max_nodes(2);
+    assert!(reader.read_from("*Vertices 3\n".as_bytes()).is_err());
+
+    reader.set_max_nodes(3);
+    assert_eq!(
+        reader
+            .read_from("*Vertices 3\n".as_bytes())
+            .unwrap()
+            .num_nodes(),
+        3
+    );
+}
+
+#[test]
+fn test_malformed_weight() {
+    let err = PajekReader::new()
+        .read("resources/nets/malformed-weight.net".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse float".to_string())
+    );
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.token(), Some("heavy"));
+}
+
+#[test]
+fn test_edge_list_is_not_pajek() {
+    let err = PajekReader::new()
+        .read("resources/nets/simple-net.txt".into())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Line outside of any section".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+}
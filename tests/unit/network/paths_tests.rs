@@ -0,0 +1,130 @@
+/// Unit tests for shortest paths
+use crate::network::model::Network;
+use crate::network::paths::*;
+
+fn weighted_net(directed: bool) -> Network<&'static str, f64> {
+    let mut net: Network<&str, f64> = Network::new(directed);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+    net.add_edge("A", "C", 5.0).unwrap();
+    net.add_edge("C", "D", 2.0).unwrap();
+    net.add_node("E");
+
+    net
+}
+
+#[test]
+fn test_bfs_unknown_source() {
+    let net = weighted_net(false);
+
+    assert_eq!(
+        bfs(&net, "X").unwrap_err(),
+        PathsError::NodeNotFound("Source node is not in the network".to_string())
+    );
+}
+
+#[test]
+fn test_bfs_hop_distances() {
+    let net = weighted_net(false);
+
+    let paths = bfs(&net, "A").unwrap();
+
+    assert_eq!(paths.source(), "A");
+    assert_eq!(paths.distance("A"), Some(0.0));
+    assert_eq!(paths.distance("B"), Some(1.0));
+    assert_eq!(paths.distance("C"), Some(1.0));
+    assert_eq!(paths.distance("D"), Some(2.0));
+    assert_eq!(paths.distance("E"), None, "E is not reachable");
+    assert_eq!(paths.distances().len(), 4);
+    assert_eq!(paths.path_to("D").unwrap(), vec!["A", "C", "D"]);
+}
+
+#[test]
+fn test_bfs_directed() {
+    let net = weighted_net(true);
+
+    let paths = bfs(&net, "C").unwrap();
+
+    assert_eq!(paths.distance("D"), Some(1.0));
+    assert_eq!(paths.distance("A"), None);
+    assert_eq!(paths.path_to("A"), None);
+}
+
+#[test]
+fn test_dijkstra_weighted_distances() {
+    let net = weighted_net(false);
+
+    let paths = dijkstra(&net, "A").unwrap();
+
+    assert_eq!(paths.distance("B"), Some(1.0));
+    assert_eq!(paths.distance("C"), Some(2.0), "Going through B is shorter");
+    assert_eq!(paths.distance("D"), Some(4.0));
+    assert_eq!(paths.distance("E"), None);
+    assert_eq!(paths.path_to("D").unwrap(), vec!["A", "B", "C", "D"]);
+    assert_eq!(paths.path_to("A").unwrap(), vec!["A"]);
+}
+
+#[test]
+fn test_dijkstra_parallel_edges() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 3.0).unwrap();
+    net.add_edge("A", "B", 1.0).unwrap();
+
+    let paths = dijkstra(&net, "A").unwrap();
+
+    assert_eq!(paths.distance("B"), Some(1.0));
+}
+
+#[test]
+fn test_dijkstra_negative_distance() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", -1.0).unwrap();
+
+    assert_eq!(
+        dijkstra(&net, "A").unwrap_err(),
+        PathsError::InvalidDistance("Edge lengths should be non-negative. Got: -1".to_string())
+    );
+}
+
+#[test]
+fn test_all_pairs_weighted() {
+    let net = weighted_net(false);
+
+    let paths = all_pairs(&net, true).unwrap();
+
+    assert_eq!(paths.distance("A", "D"), Some(4.0));
+    assert_eq!(paths.distance("D", "A"), Some(4.0));
+    assert_eq!(paths.distance("B", "B"), Some(0.0));
+    assert_eq!(paths.distance("A", "E"), None);
+    assert_eq!(paths.distance("A", "X"), None);
+    assert_eq!(paths.path("D", "A").unwrap(), vec!["D", "C", "B", "A"]);
+    assert_eq!(paths.path("A", "E"), None);
+}
+
+#[test]
+fn test_all_pairs_hops() {
+    let net = weighted_net(true);
+
+    let paths = all_pairs(&net, false).unwrap();
+
+    assert_eq!(paths.distance("A", "D"), Some(2.0));
+    assert_eq!(paths.distance("D", "A"), None);
+    assert_eq!(paths.path("A", "D").unwrap(), vec!["A", "C", "D"]);
+}
+
+#[test]
+fn test_all_pairs_matches_dijkstra() {
+    let net = weighted_net(false);
+
+    let all = all_pairs(&net, true).unwrap();
+
+    for source in ["A", "B", "C", "D", "E"].iter() {
+        let single = dijkstra(&net, *source).unwrap();
+        for target in ["A", "B", "C", "D", "E"].iter() {
+            assert_eq!(all.distance(*source, *target), single.distance(*target));
+        }
+    }
+}
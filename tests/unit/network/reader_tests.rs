@@ -1,6 +1,13 @@
 /// Unit tests for network file reader
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use ndarray::prelude::*;
+
 use crate::network::reader::*;
 
+#[allow(clippy::duplicate_mod)]
 #[path = "../utils.rs"]
 mod utils;
 
@@ -8,14 +15,14 @@ use crate::network::reader::reader_tests::utils::*;
 
 #[test]
 fn test_reading_non_existing_file() {
-    check_reader_error("/tmp/non-existing-file.txt", ReaderError::FileNotFound);
+    check_reader_error("/tmp/non-existing-file.txt", ErrorKind::FileNotFound);
 }
 
 #[test]
 fn test_malformed_not_enough_elements() {
     check_reader_error(
         "resources/nets/malformed-not-enough-elements.txt",
-        ReaderError::MalformedFileFormat("Not enough elements".to_string()),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string()),
     );
 }
 
@@ -23,7 +30,7 @@ fn test_malformed_not_enough_elements() {
 fn test_malformed_int_parse_error() {
     check_reader_error(
         "resources/nets/malformed-int-parse-error.txt",
-        ReaderError::MalformedFileFormat("Couldn't parse int".to_string()),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string()),
     );
 }
 
@@ -31,7 +38,7 @@ fn test_malformed_int_parse_error() {
 fn test_malformed_float_parse_error() {
     check_reader_error(
         "resources/nets/malformed-float-parse-error.txt",
-        ReaderError::MalformedFileFormat("Couldn't parse float".to_string()),
+        ErrorKind::MalformedFileFormat("Couldn't parse float".to_string()),
     );
 }
 
@@ -97,3 +104,659 @@ fn test_fb_forum_undirected() {
         vec![],
     );
 }
+
+#[test]
+fn test_multi_edges_and_self_loops_kept_by_default() {
+    let net = read_net("resources/nets/multi-edges-self-loops.txt", true);
+    check_network(
+        net,
+        3,
+        4,
+        vec![0, 1, 2],
+        vec![
+            (0, 1, vec![&2.0, &1.0]),
+            (1, 1, vec![&5.0]),
+            (1, 2, vec![&3.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_multi_edges_merged() {
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_multi_edge_policy(MultiEdgePolicy::sum());
+    reader.set_self_loop_policy(SelfLoopPolicy::Drop);
+
+    let net = reader
+        .read("resources/nets/multi-edges-self-loops.txt".parse().unwrap())
+        .unwrap();
+
+    check_network(
+        net,
+        3,
+        2,
+        vec![0, 1, 2],
+        vec![(0, 1, vec![&3.0]), (1, 2, vec![&3.0])],
+    );
+}
+
+#[test]
+fn test_multi_edges_rejected() {
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_multi_edge_policy(MultiEdgePolicy::Reject);
+
+    let res = reader.read("resources/nets/multi-edges-self-loops.txt".parse().unwrap());
+
+    assert_eq!(
+        *res.unwrap_err().kind(),
+        ErrorKind::InvalidNetwork(NetworkError::DuplicateEdge(
+            "Edge (0, 1) already exists".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_self_loops_rejected() {
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_self_loop_policy(SelfLoopPolicy::Error);
+
+    let res = reader.read("resources/nets/multi-edges-self-loops.txt".parse().unwrap());
+
+    assert_eq!(
+        *res.unwrap_err().kind(),
+        ErrorKind::InvalidNetwork(NetworkError::SelfLoop(
+            "Self-loop (1, 1) is not allowed".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_reading_dense_features() {
+    let mut net = read_net("resources/nets/simple-net.txt", false);
+    let reader = NodeFeaturesReader::new(',', FeaturesFormat::Dense);
+
+    reader
+        .read_into(
+            "resources/features/simple-dense.txt".parse().unwrap(),
+            &mut net,
+        )
+        .unwrap();
+
+    assert_eq!(net.feature_dim(), Some(3));
+    assert_eq!(
+        net.node_features(1).unwrap(),
+        &NodeFeatures::Dense(arr1(&[0.5, 0.5, 0.5]))
+    );
+}
+
+#[test]
+fn test_reading_sparse_features() {
+    let mut net = read_net("resources/nets/simple-net.txt", false);
+    let reader = NodeFeaturesReader::new(',', FeaturesFormat::Sparse(4));
+
+    reader
+        .read_into(
+            "resources/features/simple-sparse.txt".parse().unwrap(),
+            &mut net,
+        )
+        .unwrap();
+
+    assert_eq!(net.feature_dim(), Some(4));
+    assert_eq!(
+        net.node_features(0).unwrap().to_dense(),
+        arr1(&[1.0, 0.0, 2.0, 0.0])
+    );
+    assert_eq!(
+        net.node_features(2).unwrap().to_dense(),
+        arr1(&[0.0, 0.0, 0.0, 0.0])
+    );
+}
+
+#[test]
+fn test_reading_features_wrong_dimension() {
+    let mut net = read_net("resources/nets/simple-net.txt", false);
+    let reader = NodeFeaturesReader::new(',', FeaturesFormat::Dense);
+
+    let res = reader.read_into(
+        "resources/features/malformed-dimension.txt"
+            .parse()
+            .unwrap(),
+        &mut net,
+    );
+
+    assert_eq!(
+        *res.unwrap_err().kind(),
+        ErrorKind::InvalidNetwork(NetworkError::WrongFeatureDimension(
+            "Expected: 3 Got: 2".to_string()
+        ))
+    );
+}
+
+#[test]
+fn test_reading_labels() {
+    let mut net = read_net("resources/nets/simple-net.txt", false);
+    let reader = NodeLabelsReader::new(',');
+
+    reader
+        .read_into(
+            "resources/features/simple-labels.txt".parse().unwrap(),
+            &mut net,
+        )
+        .unwrap();
+
+    assert_eq!(net.node_label(0), Some("red"));
+    assert_eq!(net.node_label(1), Some("blue"));
+    assert_eq!(net.node_label(2), Some("red"));
+}
+
+#[test]
+fn test_reading_string_ids_interned() {
+    let reader = EdgeListReader::new(',', true);
+
+    let (net, vocab) = reader
+        .read_interned("resources/nets/string-ids-net.txt".parse().unwrap())
+        .unwrap();
+
+    assert_eq!(vocab.len(), 3);
+    let (alice, bob, carol) = (
+        vocab.id("alice").unwrap(),
+        vocab.id("bob").unwrap(),
+        vocab.id("carol").unwrap(),
+    );
+
+    check_network(
+        net,
+        3,
+        3,
+        vec![alice, bob, carol],
+        vec![
+            (alice, bob, vec![&1.0]),
+            (bob, carol, vec![&2.0]),
+            (carol, alice, vec![&3.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_reading_string_ids_not_interned() {
+    check_reader_error(
+        "resources/nets/string-ids-net.txt",
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string()),
+    );
+}
+
+#[test]
+fn test_reading_unweighted_whitespace_separated() {
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_separator(Separator::Whitespace);
+    reader.set_columns(0, 1, None);
+
+    let net = reader
+        .read("resources/nets/unweighted-whitespace.txt".parse().unwrap())
+        .unwrap();
+
+    check_network(
+        net,
+        3,
+        3,
+        vec![0, 1, 2],
+        vec![(0, 1, vec![&1.0]), (1, 2, vec![&1.0]), (2, 0, vec![&1.0])],
+    );
+}
+
+#[test]
+fn test_reading_header_and_column_mapping() {
+    let mut reader = EdgeListReader::new(';', true);
+    reader.set_skip_header(true);
+    reader.set_columns(2, 1, Some(0));
+
+    let net = reader
+        .read("resources/nets/header-columns.txt".parse().unwrap())
+        .unwrap();
+
+    check_network(
+        net,
+        3,
+        3,
+        vec![0, 1, 2],
+        vec![
+            (0, 1, vec![&16.0]),
+            (1, 2, vec![&18.0]),
+            (2, 0, vec![&20.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_reading_header_without_skipping() {
+    let mut reader = EdgeListReader::new(';', true);
+    reader.set_columns(2, 1, Some(0));
+
+    let res = reader.read("resources/nets/header-columns.txt".parse().unwrap());
+
+    assert_eq!(
+        *res.unwrap_err().kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string())
+    );
+}
+
+#[test]
+fn test_reading_unweighted_missing_column() {
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_columns(0, 3, None);
+
+    let res = reader.read("resources/nets/simple-net.txt".parse().unwrap());
+
+    assert_eq!(
+        *res.unwrap_err().kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
+    );
+}
+
+#[test]
+fn test_error_location() {
+    let reader = EdgeListReader::new(',', false);
+    let path: PathBuf = "resources/nets/malformed-float-parse-error.txt"
+        .parse()
+        .unwrap();
+
+    let err = reader.read(path.clone()).unwrap_err();
+
+    assert_eq!(err.path(), Some(path.as_path()));
+    assert_eq!(err.line(), Some(1));
+    assert_eq!(err.token(), Some("A"));
+}
+
+#[test]
+fn test_truncated_file() {
+    let reader = EdgeListReader::new(',', false);
+
+    let err = reader
+        .read("resources/nets/truncated.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
+    );
+    assert_eq!(err.line(), Some(3));
+    assert_eq!(err.token(), Some("2,0"));
+}
+
+#[test]
+fn test_invalid_utf8() {
+    let reader = EdgeListReader::new(',', false);
+
+    let err = reader
+        .read("resources/nets/invalid-utf8.txt".parse().unwrap())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::Io(io::Error::from(io::ErrorKind::InvalidData))
+    );
+    assert_eq!(err.line(), Some(2));
+}
+
+#[test]
+fn test_reading_directory() {
+    let reader = EdgeListReader::new(',', false);
+
+    let err = reader.read("resources/nets".parse().unwrap()).unwrap_err();
+
+    match err.kind() {
+        ErrorKind::Io(_) => {}
+        kind => panic!("Expected IO error, got: {:?}", kind),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = tmp_file("permission-denied.txt");
+    fs::write(&path, "0,1,1.0\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o000)).unwrap();
+
+    // Privileged users (e.g. root in containers) can open the file anyway,
+    // there is nothing to test for them
+    let privileged = fs::File::open(&path).is_ok();
+    let res = EdgeListReader::new(',', false).read(path.clone());
+    fs::remove_file(&path).unwrap();
+
+    if privileged {
+        return;
+    }
+
+    let err = res.unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::Io(io::Error::from(io::ErrorKind::PermissionDenied))
+    );
+    assert_eq!(err.path(), Some(path.as_path()));
+}
+
+#[test]
+fn test_lenient_skips_malformed_lines() {
+    let reader = EdgeListReader::new(',', true);
+
+    let (net, report) = reader
+        .read_lenient(
+            "resources/nets/partially-malformed.txt".parse().unwrap(),
+            10,
+        )
+        .unwrap();
+
+    check_network(
+        net,
+        3,
+        2,
+        vec![0, 1, 2],
+        vec![(0, 1, vec![&16.0]), (2, 0, vec![&20.0])],
+    );
+
+    assert_eq!(report.num_skipped(), 2);
+    let skipped = report.skipped();
+    assert_eq!(skipped[0].line(), Some(2));
+    assert_eq!(
+        *skipped[0].kind(),
+        ErrorKind::MalformedFileFormat("Not enough elements".to_string())
+    );
+    assert_eq!(skipped[1].line(), Some(4));
+    assert_eq!(skipped[1].token(), Some("A"));
+}
+
+#[test]
+fn test_lenient_nothing_to_skip() {
+    let reader = EdgeListReader::new(',', true);
+
+    let (net, report) = reader
+        .read_lenient("resources/nets/simple-net.txt".parse().unwrap(), 0)
+        .unwrap();
+
+    assert_eq!(net.num_edges(), 3);
+    assert_eq!(report, ReadReport::new());
+}
+
+#[test]
+fn test_lenient_limit_exceeded() {
+    let reader = EdgeListReader::new(',', true);
+
+    let err = reader
+        .read_lenient("resources/nets/partially-malformed.txt".parse().unwrap(), 1)
+        .unwrap_err();
+
+    assert_eq!(err.line(), Some(4), "Second bad line exceeds the limit");
+}
+
+#[test]
+fn test_lenient_does_not_skip_io_errors() {
+    let reader = EdgeListReader::new(',', true);
+
+    let err = reader
+        .read_lenient("/tmp/non-existing-file.txt".parse().unwrap(), 10)
+        .unwrap_err();
+
+    assert_eq!(*err.kind(), ErrorKind::FileNotFound);
+}
+
+#[test]
+fn test_adjacency_list_reader() {
+    let reader = AdjacencyListReader::new(' ', true);
+
+    let net = reader
+        .read("resources/nets/adjacency-list.txt".parse().unwrap())
+        .unwrap();
+
+    check_network(
+        net,
+        4,
+        3,
+        vec![0, 1, 2, 3],
+        vec![
+            (0, 1, vec![&16.0]),
+            (0, 2, vec![&20.0]),
+            (1, 2, vec![&18.0]),
+        ],
+    );
+}
+
+#[test]
+fn test_adjacency_list_reader_missing_weight() {
+    let reader = AdjacencyListReader::new(' ', true);
+
+    let err = reader
+        .read(
+            "resources/nets/adjacency-list-missing-weight.txt"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Every neighbour should have a weight".to_string())
+    );
+    assert_eq!(err.line(), Some(1));
+}
+
+#[test]
+fn test_reading_compressed_files() {
+    for file in [
+        "resources/nets/simple-net.txt.gz",
+        "resources/nets/simple-net.txt.bz2",
+        "resources/nets/simple-net.txt.zst",
+        "resources/nets/simple-net-gzip-no-extension",
+    ]
+    .iter()
+    {
+        let net = read_net(file, true);
+        check_network(
+            net,
+            3,
+            3,
+            vec![0, 1, 2],
+            vec![
+                (0, 1, vec![&16.0]),
+                (1, 2, vec![&18.0]),
+                (2, 0, vec![&20.0]),
+            ],
+        );
+    }
+}
+
+#[test]
+fn test_reading_corrupted_compressed_file() {
+    let err = EdgeListReader::new(',', true)
+        .read("resources/nets/corrupted.txt.gz".parse().unwrap())
+        .unwrap_err();
+
+    match err.kind() {
+        ErrorKind::Io(_) => {}
+        kind => panic!("Unexpected error: {:?}", kind),
+    }
+    assert!(err.path().is_some());
+}
+
+#[test]
+fn test_reading_from_memory() {
+    let input = "0,1,0.5\n1,2,2.0\n";
+    let net = EdgeListReader::new(',', true)
+        .read_from(input.as_bytes())
+        .unwrap();
+
+    assert_eq!(net.num_nodes(), 3);
+    assert_eq!(net.num_edges(), 2);
+    assert_eq!(net.edge_data(1, 2), Some(vec![&2.0]));
+
+    let net = AdjacencyListReader::new(' ', false)
+        .read_from(io::Cursor::new("0 1 0.5 2 1.0\n3\n"))
+        .unwrap();
+
+    assert_eq!(net.num_nodes(), 4);
+    assert_eq!(net.edge_data(2, 0), Some(vec![&1.0]));
+}
+
+#[test]
+fn test_reading_from_memory_error() {
+    let err = EdgeListReader::new(',', true)
+        .read_from("0,1,0.5\n1,x,2.0\n".as_bytes())
+        .unwrap_err();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Couldn't parse int".to_string())
+    );
+    assert_eq!(err.line(), Some(2));
+    assert_eq!(err.token(), Some("x"));
+    assert_eq!(err.path(), None);
+}
+
+#[test]
+fn test_reading_file_and_memory_agree() {
+    let path = "resources/nets/simple-net.txt";
+    let reader = EdgeListReader::new(',', false);
+
+    let from_file = reader.read(path.into()).unwrap();
+    let from_memory = reader
+        .read_from(io::BufReader::new(fs::File::open(path).unwrap()))
+        .unwrap();
+
+    let sorted_nodes = |net: &Network<usize, f64>| {
+        let mut nodes: Vec<usize> = net.nodes().into_iter().copied().collect();
+        nodes.sort();
+        nodes
+    };
+
+    assert_eq!(sorted_nodes(&from_file), sorted_nodes(&from_memory));
+    assert_eq!(from_file.num_edges(), from_memory.num_edges());
+}
+
+#[test]
+fn test_parallel_read_matches_sequential() {
+    let mut readers = vec![
+        (
+            "resources/nets/fb-forum.txt",
+            EdgeListReader::new(',', false),
+        ),
+        (
+            "resources/nets/simple-net.txt",
+            EdgeListReader::new(',', true),
+        ),
+        (
+            "resources/nets/multi-edges-self-loops.txt",
+            EdgeListReader::new(',', true),
+        ),
+    ];
+
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_separator(Separator::Whitespace);
+    reader.set_columns(0, 1, None);
+    readers.push(("resources/nets/unweighted-whitespace.txt", reader));
+
+    let mut reader = EdgeListReader::new(';', true);
+    reader.set_skip_header(true);
+    reader.set_columns(2, 1, Some(0));
+    readers.push(("resources/nets/header-columns.txt", reader));
+
+    let mut reader = EdgeListReader::new(',', false);
+    reader.set_multi_edge_policy(MultiEdgePolicy::sum());
+    readers.push(("resources/nets/multi-edges-self-loops.txt", reader));
+
+    for (file, reader) in readers.iter() {
+        let expected = reader.read(file.into()).unwrap();
+        let actual = reader.read_parallel(file.into()).unwrap();
+
+        assert_same_network(&actual, &expected);
+    }
+}
+
+#[test]
+fn test_parallel_read_errors_match_sequential() {
+    let reader = EdgeListReader::new(',', false);
+
+    for file in [
+        "/tmp/non-existing-file.txt",
+        "resources/nets/malformed-not-enough-elements.txt",
+        "resources/nets/malformed-int-parse-error.txt",
+        "resources/nets/malformed-float-parse-error.txt",
+        "resources/nets/partially-malformed.txt",
+        "resources/nets/invalid-utf8.txt",
+    ]
+    .iter()
+    {
+        assert_eq!(
+            reader.read_parallel(file.into()).unwrap_err(),
+            reader.read(file.into()).unwrap_err(),
+            "Errors differ for {}",
+            file
+        );
+    }
+
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_multi_edge_policy(MultiEdgePolicy::Reject);
+    let file = "resources/nets/multi-edges-self-loops.txt";
+
+    let err = reader.read_parallel(file.into()).unwrap_err();
+    assert_eq!(err, reader.read(file.into()).unwrap_err());
+    assert_eq!(err.line(), Some(2));
+}
+
+#[test]
+fn test_parallel_read_reports_the_first_error() {
+    let file = tmp_file("first-error.txt");
+    let mut content = "0,1\n1,2\n0,1\n2,3\nx,4\n".to_string();
+    for i in 10..1000 {
+        content.push_str(&format!("{},{}\n", i, i + 1));
+    }
+    fs::write(&file, content).unwrap();
+
+    let mut reader = EdgeListReader::new(',', false);
+    reader.set_columns(0, 1, None);
+    reader.set_multi_edge_policy(MultiEdgePolicy::Reject);
+
+    // A single thread makes the duplicate and the bad token share a chunk
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+    let err = pool
+        .install(|| reader.read_parallel(file.clone()))
+        .unwrap_err();
+
+    assert_eq!(err, reader.read(file.clone()).unwrap_err());
+    assert_eq!(err.line(), Some(3));
+
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn test_parallel_read_compressed_file() {
+    let reader = EdgeListReader::new(',', true);
+
+    let net = reader
+        .read_parallel("resources/nets/simple-net.txt.gz".into())
+        .unwrap();
+
+    assert_same_network(
+        &net,
+        &reader.read("resources/nets/simple-net.txt".into()).unwrap(),
+    );
+}
+
+#[test]
+fn test_split_lines() {
+    let data = b"0,1\n1,2\n\n2,3\n3,4";
+
+    for num_chunks in 1..20 {
+        let chunks = split_lines(data, num_chunks);
+
+        assert_eq!(chunks.concat(), data.to_vec());
+        for chunk in chunks[..chunks.len() - 1].iter() {
+            assert_eq!(chunk.last(), Some(&b'\n'));
+        }
+    }
+
+    assert!(split_lines(b"", 4).is_empty());
+}
@@ -0,0 +1,122 @@
+/// Unit tests for network statistics
+use crate::network::model::Network;
+use crate::network::stats::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::stats::stats_tests::utils::*;
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-9,
+        "Expected: {} Got: {}",
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn test_stats_empty_network() {
+    let net: Network<usize, f64> = Network::new(false);
+
+    let stats = NetworkStats::compute(&net);
+
+    assert_eq!(stats, NetworkStats::default());
+}
+
+#[test]
+fn test_stats_undirected() {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "C", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+    net.add_edge("A", "D", 1.0).unwrap();
+    net.add_node("E");
+
+    let stats = NetworkStats::compute(&net);
+
+    assert_eq!(stats.num_nodes, 5);
+    assert_eq!(stats.num_edges, 4);
+    assert_close(stats.density, 0.4);
+    assert_eq!(stats.num_self_loops, 0);
+    assert_eq!(stats.num_multi_edges, 0);
+    assert_eq!(stats.num_isolated_nodes, 1, "Only E should be isolated");
+    assert_close(stats.mean_degree, 1.6);
+    assert_eq!(
+        stats.degree_percentiles,
+        DegreePercentiles {
+            min: 0,
+            p25: 1,
+            median: 2,
+            p75: 2,
+            p90: 3,
+            p99: 3,
+            max: 3,
+        }
+    );
+    assert_eq!(stats.num_components, 2, "E should form its own component");
+    assert_close(stats.avg_clustering_coefficient, 7.0 / 15.0);
+    assert_close(stats.degree_assortativity.unwrap(), -5.0 / 7.0);
+}
+
+#[test]
+fn test_stats_directed_self_loops_and_multi_edges() {
+    let mut net: Network<&str, f64> = Network::new(true);
+
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "B", 2.0).unwrap();
+    net.add_edge("A", "A", 1.0).unwrap();
+    net.add_edge("B", "C", 1.0).unwrap();
+
+    let stats = NetworkStats::compute(&net);
+
+    assert_eq!(stats.num_nodes, 3);
+    assert_eq!(stats.num_edges, 4);
+    assert_close(stats.density, 4.0 / 6.0);
+    assert_eq!(stats.num_self_loops, 1);
+    assert_eq!(stats.num_multi_edges, 1);
+    assert_eq!(stats.num_isolated_nodes, 0);
+    assert_eq!(
+        stats.degree_percentiles.max, 4,
+        "A: 2 parallel edges + 2 loop ends"
+    );
+    assert_eq!(stats.num_components, 1);
+    assert_close(stats.avg_clustering_coefficient, 0.0);
+}
+
+#[test]
+fn test_stats_undirected_self_loops_and_multi_edges() {
+    let mut net: Network<&str, f64> = Network::new(false);
+
+    net.add_edge("A", "A", 1.0).unwrap();
+    net.add_edge("A", "B", 1.0).unwrap();
+    net.add_edge("A", "B", 2.0).unwrap();
+
+    let stats = NetworkStats::compute(&net);
+
+    assert_eq!(stats.num_edges, 3);
+    assert_eq!(stats.num_self_loops, 1);
+    assert_eq!(stats.num_multi_edges, 1);
+    assert_eq!(stats.degree_percentiles.min, 2);
+    assert_eq!(stats.degree_percentiles.max, 4);
+}
+
+#[test]
+fn test_stats_simple_file() {
+    let net = read_net("resources/nets/simple-net.txt", false);
+
+    let stats = NetworkStats::compute(&net);
+
+    assert_eq!(stats.num_nodes, 3);
+    assert_eq!(stats.num_edges, 3);
+    assert_close(stats.density, 1.0);
+    assert_eq!(stats.num_components, 1);
+    assert_close(stats.avg_clustering_coefficient, 1.0);
+    assert!(
+        stats.degree_assortativity.is_none(),
+        "Assortativity is undefined for regular networks"
+    );
+}
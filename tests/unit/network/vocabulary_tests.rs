@@ -0,0 +1,55 @@
+/// Unit tests for node id vocabulary
+use crate::embedding::model::{Embedding, EmbeddingError};
+use crate::network::vocabulary::*;
+
+#[test]
+fn test_vocabulary_empty() {
+    let vocab = Vocabulary::new();
+
+    assert!(vocab.is_empty());
+    assert_eq!(vocab.id("A"), None);
+    assert_eq!(vocab.name(0), None);
+}
+
+#[test]
+fn test_intern_assigns_consecutive_ids() {
+    let mut vocab = Vocabulary::new();
+
+    assert_eq!(vocab.intern("A"), 0);
+    assert_eq!(vocab.intern("B"), 1);
+    assert_eq!(vocab.intern("A"), 0, "Known name should keep its id");
+
+    assert_eq!(vocab.len(), 2);
+    assert_eq!(vocab.id("B"), Some(1));
+    assert_eq!(vocab.name(0), Some("A"));
+}
+
+#[test]
+fn test_decode_embedding() {
+    let mut vocab = Vocabulary::new();
+    vocab.intern("A");
+    vocab.intern("B");
+
+    let mut emb: Embedding<usize> = Embedding::new(2);
+    emb.add_vector(0, &[1.0, 2.0]).unwrap();
+    emb.add_vector(1, &[3.0, 4.0]).unwrap();
+
+    let decoded = vocab.decode_embedding(&emb).unwrap();
+
+    assert_eq!(decoded.dim(), 2);
+    assert_eq!(decoded.get_vector("A").unwrap(), &[1.0, 2.0]);
+    assert_eq!(decoded.get_vector("B").unwrap(), &[3.0, 4.0]);
+}
+
+#[test]
+fn test_decode_embedding_unknown_id() {
+    let vocab = Vocabulary::new();
+
+    let mut emb: Embedding<usize> = Embedding::new(1);
+    emb.add_vector(7, &[1.0]).unwrap();
+
+    assert_eq!(
+        vocab.decode_embedding(&emb).unwrap_err(),
+        EmbeddingError::VectorNotFound("No name found for 7".to_string())
+    );
+}
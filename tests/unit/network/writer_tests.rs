@@ -0,0 +1,181 @@
+/// Unit tests for network file writer
+use std::fs;
+
+use rand::prelude::*;
+
+use crate::network::model::Network;
+use crate::network::reader::*;
+use crate::network::writer::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "../utils.rs"]
+mod utils;
+
+use crate::network::writer::writer_tests::utils::*;
+
+const NUM_RANDOM_NETWORKS: u64 = 50;
+
+/// Random network with self-loops and parallel edges. Isolated nodes are
+/// only added if requested, as edge lists can't represent them.
+fn random_network(seed: u64, directed: bool, isolated: bool) -> Network<usize, f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut net: Network<usize, f64> = Network::new(directed);
+
+    let num_nodes = rng.gen_range(1, 20);
+    let num_edges = rng.gen_range(1, 50);
+
+    for _ in 0..num_edges {
+        let from = rng.gen_range(0, num_nodes);
+        let to = rng.gen_range(0, num_nodes);
+        net.add_edge(from, to, rng.gen::<f64>() * 100.0).unwrap();
+    }
+
+    if isolated {
+        for node in num_nodes..num_nodes + rng.gen_range(0, 3) {
+            net.add_node(node);
+        }
+    }
+
+    net
+}
+
+fn round_trip<W, R>(writer: &W, reader: &R, net: &Network<usize, f64>, name: &str)
+where
+    W: NetworkWriter<usize, f64>,
+    R: NetworkReader<N = usize, E = f64>,
+{
+    let file = tmp_file(&format!("{}.txt", name));
+
+    writer.write(net, file.clone()).unwrap();
+    let read = reader.read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_same_network(&read, net);
+}
+
+#[test]
+fn test_edge_list_writer_output() {
+    let mut net: Network<usize, f64> = Network::new(true);
+    net.add_edge(0, 1, 16.0).unwrap();
+    net.add_edge(1, 2, 18.5).unwrap();
+    net.add_edge(0, 2, 20.0).unwrap();
+
+    let file = tmp_file("edge-list-output.txt");
+    EdgeListWriter::new(',').write(&net, file.clone()).unwrap();
+
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(content, "0,1,16\n0,2,20\n1,2,18.5\n");
+}
+
+#[test]
+fn test_edge_list_writer_undirected_written_once() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.add_edge(1, 0, 2.0).unwrap();
+    net.add_edge(0, 0, 3.0).unwrap();
+
+    let mut writer = EdgeListWriter::new(',');
+    writer.set_separator(Separator::Whitespace);
+    writer.set_write_weights(false);
+
+    let file = tmp_file("edge-list-undirected.txt");
+    writer.write(&net, file.clone()).unwrap();
+
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(content, "1 0\n0 0\n");
+}
+
+#[test]
+fn test_adjacency_list_writer_output() {
+    let mut net: Network<usize, f64> = Network::new(true);
+    net.add_edge(0, 1, 16.0).unwrap();
+    net.add_edge(0, 2, 20.0).unwrap();
+    net.add_node(3);
+
+    let file = tmp_file("adjacency-list-output.txt");
+    AdjacencyListWriter::new(',')
+        .write(&net, file.clone())
+        .unwrap();
+
+    let content = fs::read_to_string(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(content, "0,1,16,2,20\n1\n2\n3\n");
+}
+
+#[test]
+fn test_writing_to_missing_directory() {
+    let net: Network<usize, f64> = Network::new(true);
+
+    let res = EdgeListWriter::new(',').write(&net, "/tmp/non-existing-dir/net.txt".into());
+
+    assert!(res.is_err());
+}
+
+#[test]
+fn test_edge_list_round_trip() {
+    for seed in 0..NUM_RANDOM_NETWORKS {
+        for directed in [true, false].iter() {
+            let net = random_network(seed, *directed, false);
+
+            round_trip(
+                &EdgeListWriter::new(','),
+                &EdgeListReader::new(',', *directed),
+                &net,
+                "edge-list-round-trip",
+            );
+        }
+    }
+}
+
+#[test]
+fn test_edge_list_round_trip_whitespace() {
+    let net = random_network(42, true, false);
+
+    let mut writer = EdgeListWriter::new(',');
+    writer.set_separator(Separator::Whitespace);
+    let mut reader = EdgeListReader::new(',', true);
+    reader.set_separator(Separator::Whitespace);
+
+    round_trip(&writer, &reader, &net, "edge-list-round-trip-ws");
+}
+
+#[test]
+fn test_adjacency_list_round_trip() {
+    for seed in 0..NUM_RANDOM_NETWORKS {
+        for directed in [true, false].iter() {
+            let net = random_network(seed, *directed, true);
+
+            round_trip(
+                &AdjacencyListWriter::new(' '),
+                &AdjacencyListReader::new(' ', *directed),
+                &net,
+                "adjacency-list-round-trip",
+            );
+        }
+    }
+}
+
+#[test]
+fn test_adjacency_list_round_trip_unweighted() {
+    let mut net: Network<usize, f64> = Network::new(false);
+    net.add_edge(0, 1, 1.0).unwrap();
+    net.add_edge(1, 2, 1.0).unwrap();
+    net.add_edge(2, 2, 1.0).unwrap();
+    net.add_node(3);
+
+    let mut writer = AdjacencyListWriter::new(',');
+    writer.set_write_weights(false);
+    let mut reader = AdjacencyListReader::new(',', false);
+    reader.set_weighted(false);
+
+    round_trip(
+        &writer,
+        &reader,
+        &net,
+        "adjacency-list-round-trip-unweighted",
+    );
+}
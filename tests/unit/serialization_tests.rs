@@ -0,0 +1,188 @@
+/// Unit tests for serde support
+use serde_json::json;
+
+use crate::algorithm::random_walk::*;
+use crate::embedding::model::*;
+use crate::network::model::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "utils.rs"]
+mod utils;
+
+use crate::serialization::serialization_tests::utils::*;
+
+fn sample_network(directed: bool) -> Network<usize, f64> {
+    let mut net = Network::new(directed);
+    net.add_edge(0, 1, 1.5).unwrap();
+    net.add_edge(1, 2, 2.5).unwrap();
+    net.add_edge(2, 2, 3.0).unwrap();
+    net.add_edge(0, 1, 4.0).unwrap();
+    net.add_node(3);
+    net.set_node_label(1, "one");
+    net.set_node_features(2, NodeFeatures::Dense(vec![1.0, 2.0].into()))
+        .unwrap();
+    net.set_node_features(
+        3,
+        NodeFeatures::Sparse {
+            dim: 2,
+            entries: vec![(1, 0.5)],
+        },
+    )
+    .unwrap();
+
+    net
+}
+
+#[test]
+fn test_network_json() {
+    let value = serde_json::to_value(sample_network(false)).unwrap();
+
+    assert_eq!(
+        value,
+        json!({
+            "directed": false,
+            "nodes": [0, 1, 2, 3],
+            "edges": [[0, 1, 1.5], [1, 2, 2.5], [2, 2, 3.0], [0, 1, 4.0]],
+            "labels": [[1, "one"]],
+            "features": [
+                [2, {"Dense": [1.0, 2.0]}],
+                [3, {"Sparse": {"dim": 2, "entries": [[1, 0.5]]}}]
+            ]
+        })
+    );
+}
+
+#[test]
+fn test_undirected_edges_listed_once() {
+    let mut net = Network::new(false);
+    net.add_edge(1, 0, 1.0).unwrap();
+    net.add_edge(0, 1, 2.0).unwrap();
+    net.add_edge(0, 0, 3.0).unwrap();
+
+    let value = serde_json::to_value(&net).unwrap();
+    assert_eq!(
+        value["edges"],
+        json!([[1, 0, 1.0], [1, 0, 2.0], [0, 0, 3.0]])
+    );
+
+    let read: Network<usize, f64> = serde_json::from_value(value).unwrap();
+    assert_same_network(&read, &net);
+    assert_eq!(read.edge_data(0, 1), net.edge_data(0, 1));
+    assert_eq!(read.edge_data(1, 0), net.edge_data(1, 0));
+}
+
+#[test]
+fn test_network_round_trip() {
+    for directed in [true, false].iter() {
+        let net = sample_network(*directed);
+
+        let json = serde_json::to_string(&net).unwrap();
+        let read: Network<usize, f64> = serde_json::from_str(&json).unwrap();
+
+        assert_same_network(&read, &net);
+        assert_eq!(read.is_directed(), *directed);
+        assert_eq!(read.node_index(3), net.node_index(3));
+        assert_eq!(read.node_label(1), Some("one"));
+        assert_eq!(read.node_features(3), net.node_features(3));
+        assert_eq!(read.neighbours_of(0), net.neighbours_of(0));
+    }
+}
+
+#[test]
+fn test_network_invalid_features() {
+    let json = r#"{
+        "directed": true,
+        "nodes": [0, 1],
+        "edges": [],
+        "features": [[0, {"Dense": [1.0]}], [1, {"Dense": [1.0, 2.0]}]]
+    }"#;
+
+    let res: Result<Network<usize, f64>, _> = serde_json::from_str(json);
+    assert!(
+        res.is_err(),
+        "Features of different dimensions are rejected"
+    );
+}
+
+#[test]
+fn test_network_undeclared_nodes() {
+    for json in [
+        r#"{"directed": true, "nodes": [0], "edges": [[0, 1, 1.0]]}"#,
+        r#"{"directed": false, "nodes": [1], "edges": [[0, 1, 1.0]]}"#,
+        r#"{"directed": true, "nodes": [0], "edges": [], "labels": [[2, "two"]]}"#,
+        r#"{"directed": true, "nodes": [0], "edges": [], "features": [[2, {"Dense": [1.0]}]]}"#,
+    ]
+    .iter()
+    {
+        let err = serde_json::from_str::<Network<usize, f64>>(json).unwrap_err();
+        assert!(
+            err.to_string().starts_with("undeclared node"),
+            "Unexpected error for {}: {}",
+            json,
+            err
+        );
+    }
+}
+
+#[test]
+fn test_embedding_round_trip() {
+    let mut emb: Embedding<(u8, u32)> = Embedding::new(2);
+    emb.add_vector((0, 7), &[1.0, 2.0]).unwrap();
+    emb.add_vector((1, 7), &[3.0, -4.0]).unwrap();
+
+    let json = serde_json::to_string(&emb).unwrap();
+    let read: Embedding<(u8, u32)> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(read.dim(), 2);
+    assert_eq!(read.keys().len(), 2);
+    assert_eq!(read.get_vector((1, 7)), Ok(&[3.0, -4.0][..]));
+}
+
+#[test]
+fn test_embedding_wrong_dimension() {
+    let json = r#"{"dim": 2, "vectors": [[0, [1.0, 2.0, 3.0]]]}"#;
+
+    let res: Result<Embedding<usize>, _> = serde_json::from_str(json);
+    assert!(res.is_err(), "Vectors of the wrong dimension are rejected");
+}
+
+#[test]
+fn test_embedding_duplicate_key() {
+    let json = r#"{"dim": 1, "vectors": [[0, [1.0]], [1, [2.0]], [0, [3.0]]]}"#;
+
+    let err = serde_json::from_str::<Embedding<usize>>(json).unwrap_err();
+    assert!(err.to_string().starts_with("duplicate key 0"));
+}
+
+#[test]
+fn test_embedding_error_round_trip() {
+    let err = EmbeddingError::VectorNotFound("No vector found for 3".to_string());
+
+    let json = serde_json::to_string(&err).unwrap();
+    let read: EmbeddingError = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(read, err);
+}
+
+#[test]
+fn test_walker_config() {
+    let mut walker = NonBiasedRandomWalker::new(10, 80);
+    walker.set_seed(42);
+
+    let value = serde_json::to_value(&walker).unwrap();
+    assert_eq!(
+        value,
+        json!({"walks_per_node": 10, "walk_length": 80, "seed": 42})
+    );
+
+    let mut read: NonBiasedRandomWalker = serde_json::from_value(value).unwrap();
+    assert_eq!(read.walks_per_node(), 10);
+    assert_eq!(read.walk_length(), 80);
+
+    let net = sample_network(false);
+    assert_eq!(read.walk(&net, 0), walker.walk(&net, 0));
+
+    let read: NonBiasedRandomWalker =
+        serde_json::from_str(r#"{"walks_per_node": 1, "walk_length": 5}"#).unwrap();
+    assert_eq!(read.seed(), None);
+}
@@ -0,0 +1,262 @@
+/// Unit tests for binary snapshots
+use std::fs;
+
+use rand::prelude::*;
+
+use crate::network::reader::*;
+
+#[allow(clippy::duplicate_mod)]
+#[path = "utils.rs"]
+mod utils;
+
+use crate::snapshot::snapshot_tests::utils::*;
+use crate::snapshot::*;
+
+fn random_network(seed: u64, directed: bool) -> Network<usize, f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut net: Network<usize, f64> = Network::new(directed);
+
+    let num_nodes = rng.gen_range(1, 20);
+    for _ in 0..rng.gen_range(1, 50) {
+        let from = rng.gen_range(0, num_nodes);
+        let to = rng.gen_range(0, num_nodes);
+        net.add_edge(from, to, rng.gen::<f64>() * 100.0).unwrap();
+    }
+
+    net.add_node(num_nodes);
+    net.set_node_label(0, "zero");
+    net.set_node_features(0, NodeFeatures::Dense(vec![1.0, 2.0, 3.0].into()))
+        .unwrap();
+    net.set_node_features(
+        num_nodes,
+        NodeFeatures::Sparse {
+            dim: 3,
+            entries: vec![(2, 0.5)],
+        },
+    )
+    .unwrap();
+
+    net
+}
+
+fn write_network(net: &Network<usize, f64>, name: &str) -> Vec<u8> {
+    let file = tmp_file(&format!("{}.snap", name));
+    NetworkSnapshotWriter::new()
+        .write(net, file.clone())
+        .unwrap();
+
+    let data = fs::read(&file).unwrap();
+    fs::remove_file(file).unwrap();
+
+    data
+}
+
+fn read_network(data: &[u8]) -> Result<Network<usize, f64>, ReaderError> {
+    NetworkSnapshotReader::new().read_from(data)
+}
+
+#[test]
+fn test_network_round_trip() {
+    for seed in 0..50 {
+        for directed in [true, false].iter() {
+            let net = random_network(seed, *directed);
+            let read = read_network(&write_network(&net, "round-trip")).unwrap();
+
+            assert_same_network(&read, &net);
+            assert_eq!(read.is_directed(), net.is_directed());
+
+            let graph = net.graph();
+            for nx in graph.node_indices() {
+                let node = graph[nx];
+                assert_eq!(read.node_index(node), Some(nx));
+                assert_eq!(read.node_label(node), net.node_label(node));
+                assert_eq!(read.node_features(node), net.node_features(node));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_network_round_trip_through_file() {
+    let net = random_network(7, false);
+    let file = tmp_file("through-file.snap");
+
+    NetworkSnapshotWriter::new()
+        .write(&net, file.clone())
+        .unwrap();
+    let read: Network<usize, f64> = NetworkSnapshotReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_same_network(&read, &net);
+}
+
+#[test]
+fn test_other_value_types() {
+    let mut net: Network<i32, f32> = Network::new(true);
+    net.add_edge(-1, 2, 0.5).unwrap();
+    net.add_edge(2, 2, 1.5).unwrap();
+
+    let file = tmp_file("other-types.snap");
+    NetworkSnapshotWriter::new()
+        .write(&net, file.clone())
+        .unwrap();
+
+    let read: Network<i32, f32> = NetworkSnapshotReader::new().read(file.clone()).unwrap();
+    assert_eq!(read.edge_data(-1, 2), Some(vec![&0.5]));
+    assert_eq!(read.edge_data(2, 2), Some(vec![&1.5]));
+
+    let err = NetworkSnapshotReader::<i64, f32>::new()
+        .read(file.clone())
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Snapshot holds i32 values, expected i64".to_string())
+    );
+    assert_eq!(err.path(), Some(file.as_path()));
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn test_embedding_round_trip() {
+    let mut emb: Embedding<usize> = Embedding::new(3);
+    emb.add_vector(0, &[1.0, 2.0, 3.0]).unwrap();
+    emb.add_vector(42, &[-1.5, 0.0, 1e-10]).unwrap();
+
+    let file = tmp_file("embedding.snap");
+    EmbeddingSnapshotWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+
+    let read = EmbeddingSnapshotReader::new().read(file.clone()).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(read.dim(), 3);
+    assert_eq!(read.keys().len(), 2);
+    assert_eq!(read.get_vector(0), emb.get_vector(0));
+    assert_eq!(read.get_vector(42), emb.get_vector(42));
+}
+
+#[test]
+fn test_embedding_key_types() {
+    let mut emb: Embedding<u32> = Embedding::new(2);
+    emb.add_vector(7, &[1.0, 2.0]).unwrap();
+
+    let file = tmp_file("embedding-keys.snap");
+    EmbeddingSnapshotWriter::new()
+        .write(&emb, file.clone())
+        .unwrap();
+
+    let read = EmbeddingSnapshotReader::<u32>::with_key_type()
+        .read(file.clone())
+        .unwrap();
+    assert_eq!(read.get_vector(7), emb.get_vector(7));
+
+    let err = EmbeddingSnapshotReader::new()
+        .read(file.clone())
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Snapshot holds u32 values, expected usize".to_string())
+    );
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn test_version_mismatch() {
+    let mut data = write_network(&random_network(0, true), "version");
+    data[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+
+    let err = read_network(&data).unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::VersionMismatch {
+            found: FORMAT_VERSION + 1,
+            supported: FORMAT_VERSION,
+        }
+    );
+}
+
+#[test]
+fn test_version_zero() {
+    let mut data = write_network(&random_network(0, true), "version-zero");
+    data[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&0u16.to_le_bytes());
+
+    let err = read_network(&data).unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Unknown format version".to_string())
+    );
+    assert_eq!(err.token(), Some("0"));
+}
+
+#[test]
+fn test_embedding_duplicate_key() {
+    let mut payload = Vec::new();
+    1usize.encode(&mut payload);
+    2usize.encode(&mut payload);
+    for (key, value) in [(7usize, 1.0), (7, 2.0)].iter() {
+        key.encode(&mut payload);
+        value.encode(&mut payload);
+    }
+
+    let file = tmp_file("duplicate-key.snap");
+    write_snapshot(
+        file.clone(),
+        Content::Embedding,
+        [usize::TYPE_NAME, f64::TYPE_NAME],
+        &payload,
+    )
+    .unwrap();
+
+    let err = EmbeddingSnapshotReader::new()
+        .read(file.clone())
+        .unwrap_err();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat("Duplicate key in snapshot".to_string())
+    );
+}
+
+#[test]
+fn test_corrupted_snapshots() {
+    let data = write_network(&random_network(0, true), "corrupted");
+
+    let mut flipped = data.clone();
+    *flipped.last_mut().unwrap() ^= 0xff;
+
+    let mut trailing = data.clone();
+    trailing.push(0);
+
+    for (input, msg) in [
+        (flipped, "Checksum mismatch"),
+        (data[..data.len() - 1].to_vec(), "Unexpected end of file"),
+        (trailing, "Trailing data after snapshot"),
+        (b"0,1,1.0\n".to_vec(), "Not a snapshot file"),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            *read_network(input).unwrap_err().kind(),
+            ErrorKind::MalformedFileFormat(msg.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_wrong_content() {
+    let data = write_network(&random_network(0, true), "content");
+
+    let err = EmbeddingSnapshotReader::new()
+        .read_from(data.as_slice())
+        .unwrap_err();
+    assert_eq!(
+        *err.kind(),
+        ErrorKind::MalformedFileFormat(
+            "Snapshot holds a network, expected an embedding".to_string()
+        )
+    );
+}
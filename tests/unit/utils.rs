@@ -1,16 +1,18 @@
 /// Test utils
+use std::env;
 use std::path::PathBuf;
+use std::process;
 
 use crate::network::model::Network;
 use crate::network::reader::*;
 
 #[allow(dead_code)]
-pub fn check_reader_error(file: &str, expected_err: ReaderError) {
+pub fn check_reader_error(file: &str, expected_err: ErrorKind) {
     let reader = EdgeListReader::new(',', false);
     let path: PathBuf = file.parse().unwrap();
 
     let res = reader.read(path);
-    assert_eq!(res.unwrap_err(), expected_err);
+    assert_eq!(*res.unwrap_err().kind(), expected_err);
 }
 
 #[allow(dead_code)]
@@ -45,3 +47,29 @@ pub fn read_net(file: &str, directed: bool) -> Network<usize, f64> {
 
     res.unwrap()
 }
+
+#[allow(dead_code)]
+pub fn assert_same_network(actual: &Network<usize, f64>, expected: &Network<usize, f64>) {
+    assert_eq!(actual.num_nodes(), expected.num_nodes());
+    assert_eq!(actual.num_edges(), expected.num_edges());
+
+    for node in expected.nodes() {
+        assert!(actual.nodes().contains(&node));
+    }
+
+    for (from, to) in expected.edges() {
+        assert_eq!(
+            actual.edge_data(*from, *to),
+            expected.edge_data(*from, *to),
+            "Edge data differs for ({}, {})",
+            from,
+            to
+        );
+    }
+}
+
+/// Path of `name` in the temp directory, unique to the test process
+#[allow(dead_code)]
+pub fn tmp_file(name: &str) -> PathBuf {
+    env::temp_dir().join(format!("rusty-nets-{}-{}", process::id(), name))
+}